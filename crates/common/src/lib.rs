@@ -1,5 +1,4 @@
 use serde::{Deserialize, Serialize};
-use strum_macros;
 
 #[derive(Debug, Clone)]
 pub struct Progress {
@@ -8,6 +7,16 @@ pub struct Progress {
 
 pub type PlayerName = String;
 
+// Sent by the watch modes of the tools, which run them again whenever their files change.
+#[derive(Debug, Clone)]
+pub enum WatchUpdate {
+	Working(Progress),
+	Finished {
+		run: u32,
+		result: Result<(), errors::ToolError>,
+	},
+}
+
 #[derive(
 	Debug,
 	Clone,
@@ -66,6 +75,8 @@ pub enum TeamName {
 	Vidya,
 }
 
+pub mod watch;
+
 pub mod errors {
 	use image::ImageError;
 	use std::{ffi::OsString, io, sync::Arc};
@@ -75,6 +86,8 @@ pub mod errors {
 
 	#[derive(Error, Debug, Clone)]
 	pub enum ToolError {
+		#[error("{0}")]
+		ConfigError(#[from] ConfigError),
 		#[error("{0}")]
 		CreationError(#[from] CreationError),
 		#[error("{0}")]
@@ -97,6 +110,8 @@ pub mod errors {
 		IoError(#[from] Arc<io::Error>),
 		#[error("{0}")]
 		ImageError(#[from] Arc<ImageError>),
+		#[error("{0}")]
+		OutputError(#[from] OutputError),
 	}
 
 	impl From<io::Error> for ToolError {
@@ -111,6 +126,28 @@ pub mod errors {
 		}
 	}
 
+	#[derive(Error, Debug, Clone)]
+	pub enum ConfigError {
+		#[error("Alias '{0}' is used by both player '{1}' and player '{2}'.")]
+		AmbiguousAlias(String, String, String),
+		#[error("Player ID '{0}' is used more than once.")]
+		DuplicatePlayerID(String),
+		#[error("Failed to read config '{0}': {1}")]
+		InvalidConfig(String, String),
+		#[error("Failed to read player identities '{0}': {1}")]
+		InvalidIdentities(String, String),
+		#[error("Failed to read manifest '{0}': {1}")]
+		InvalidManifest(String, String),
+		#[error("'{0}' isn't a valid file pattern: {1}.")]
+		InvalidPattern(String, String),
+		#[error("Failed to read roster '{0}': {1}")]
+		InvalidRoster(String, String),
+		#[error("Manifest '{0}' lists '{1}', but the file doesn't exist.")]
+		MissingManifestCup(String, String),
+		#[error("Roster folder '{0}' doesn't exist.")]
+		MissingRosterFolder(String),
+	}
+
 	#[derive(Error, Debug, Clone)]
 	pub enum CreationError {
 		#[error("No roster files round")]
@@ -121,6 +158,8 @@ pub mod errors {
 
 	#[derive(Error, Debug, Clone)]
 	pub enum EntryError {
+		#[error("Failed to finish running a tournament: {0}.")]
+		CupTaskFailure(String),
 		#[error("Failed to retrieve matchup history for {0}.")]
 		MissingTeamMatchups(TeamName),
 		#[error("Failed to retrieve participations for {0}.")]
@@ -129,12 +168,33 @@ pub mod errors {
 		MissingTeamPlacement(String, TeamName),
 		#[error("No tournament files have been found.")]
 		MissingTournamentFiles,
+		#[error("Players aren't on their team's roster:\n{0}")]
+		RosterViolations(String),
+		#[error("Failed to start the server on port {0}: {1}.")]
+		ServerStartFailure(u16, String),
 		#[error("Failed to read file(s) in the source path: {0}.")]
 		SourcePathReadError(String),
+		#[error("Failed to finish writing {0}: {1}.")]
+		WriteTaskFailure(String, String),
+	}
+
+	// Errors of the optional output formats, as text, so common doesn't need their crates.
+	#[derive(Error, Debug, Clone)]
+	pub enum OutputError {
+		#[error("Failed to write the SQLite database: {0}.")]
+		DatabaseFailure(String),
+		#[error("Failed to write CSV: {0}.")]
+		CsvFailure(String),
+		#[error("Failed to write JSON: {0}.")]
+		JsonFailure(String),
+		#[error("Failed to write YAML: {0}.")]
+		YamlFailure(String),
 	}
 
 	#[derive(Error, Debug, Clone)]
 	pub enum FixtureError {
+		#[error("{0} vs {1}: The scorers or assisters of {2} don't match its goal events.")]
+		GoalEventsMismatch(String, String, String),
 		#[error("{0} vs {1}: Couldn't determine a winner, because pen1 and pen2 are equal.")]
 		InvalidPenalties(String, String),
 		#[error("{0} vs {1}: Expected pen1, found pen2 = {2}.")]
@@ -183,6 +243,8 @@ pub mod errors {
 			"{0}: Comparing {1} & {2} group stage performance, but missing at least one team."
 		)]
 		ComparisonMissingTeam(String, TeamName, TeamName),
+		#[error("{0}: {1} is listed more than once in the teams list.")]
+		DuplicateParticipant(String, TeamName),
 		#[error("{0}: {1} vs {2}: Goals entered were '{3}', but found '{4}' goal scorers.")]
 		GoalsMismatch(String, TeamName, TeamName, u8, usize),
 		#[error("Missing or incorrect head-to-head: {0} ({1}): Couldn't resolve ordering between {2} and {3}.")]
@@ -197,18 +259,26 @@ pub mod errors {
 		InvalidGrandFinal(String, String),
 		#[error("{0} (Groups): {1} vs {2} is missing a group.")]
 		MissingGroupID(String, TeamName, TeamName),
+		#[error("{0}: {1} is listed in group {2}, but didn't play any fixtures in it.")]
+		MissingGroupMember(String, TeamName, String),
 		#[error("{0}: Ran group stage, despite no group stage existing.")]
 		MissingGroupStage(String),
 		#[error("{0}: Found no qualifying teams from group stage when sorting.")]
 		MissingQualifiedTeams(String),
 		#[error("{0}: Missing wildcard candidate.")]
 		MissingWildcard(String),
+		#[error("{0}: {1} is listed in the teams list, but didn't play any fixtures.")]
+		ParticipantDidNotPlay(String, TeamName),
 		#[error("{0} (Playoffs): {1} vs {2} ended in draw.")]
 		PlayoffFixtureDraw(String, TeamName, TeamName),
 		#[error("{0}: {1} vs {2}: Goals entered were '{3}', but found '{4}' assists.")]
 		TooManyAssists(String, TeamName, TeamName, u8, usize),
+		#[error("{0}: {1} played {2}, but isn't in the teams list.")]
+		UnlistedParticipant(String, TeamName, TeamName),
 		#[error("{0} ({1}): {2}")]
 		UpdateTeamsFailure(String, String, String),
+		#[error("{0}: {1} played in group {2}, but is listed in group {3}.")]
+		WrongGroup(String, TeamName, String, String),
 		#[error("This should never fail: SORTING_PREV_FIXTURE_ERROR.")]
 		SortingPreviousFixtureError,
 	}
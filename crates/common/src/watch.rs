@@ -0,0 +1,44 @@
+use std::{
+	collections::BTreeMap,
+	path::PathBuf,
+	time::{Duration, SystemTime},
+};
+
+use tokio::fs;
+
+// How often the watch modes check their files for changes.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Modification times of the files a tool reads, to notice when it has to run again. Files
+// that don't exist are kept too, so creating them counts as a change.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Snapshot(BTreeMap<PathBuf, Option<SystemTime>>);
+
+impl Snapshot {
+	pub async fn of(files: impl IntoIterator<Item = PathBuf>) -> Snapshot {
+		let mut snapshot = BTreeMap::new();
+		for file in files {
+			let modified = fs::metadata(&file).await.and_then(|m| m.modified()).ok();
+			snapshot.insert(file, modified);
+		}
+		Snapshot(snapshot)
+	}
+
+	// Files that were added, removed or modified since the previous snapshot.
+	pub fn changed_since(&self, previous: &Snapshot) -> Vec<PathBuf> {
+		let mut changed: Vec<PathBuf> = self
+			.0
+			.iter()
+			.filter(|(file, modified)| previous.0.get(*file) != Some(modified))
+			.map(|(file, _)| file.clone())
+			.collect();
+		changed.extend(
+			previous
+				.0
+				.keys()
+				.filter(|file| !self.0.contains_key(*file))
+				.cloned(),
+		);
+		changed
+	}
+}
@@ -3,7 +3,7 @@ use std::{
 	path::{Path, PathBuf},
 };
 
-use iced::task::{sipper, Straw};
+use iced::task::{sipper, Sipper, Straw};
 use image::ImageReader;
 use tokio::fs;
 
@@ -44,6 +44,46 @@ pub fn create_team_and_portraits(
 			return Err(CreationError::NoRosterFiles.into());
 		}
 
+		create_teams(rosterfiles, source, destination)
+			.run(&progress)
+			.await
+	})
+}
+
+// Only creates the teams whose roster file or portraits are among the changed files.
+pub fn recreate_changed_teams(
+	source: PathBuf,
+	destination: PathBuf,
+	changed: Vec<PathBuf>,
+) -> impl Straw<(), Progress, ToolError> {
+	sipper(async move |mut progress| {
+		let _ = progress.send(Progress { percent: 0.0 }).await;
+
+		let rosterfiles: Vec<RosterFile> = RosterFile::get_rosterfiles(&source)
+			.await?
+			.into_iter()
+			.filter(|roster_file| {
+				let dds_folder = dds_folder(&roster_file.team, &source);
+				changed.iter().any(|file| {
+					file == &roster_file.path
+						|| file == &dds_folder
+						|| file.parent() == Some(&dds_folder)
+				})
+			})
+			.collect();
+
+		create_teams(rosterfiles, source, destination)
+			.run(&progress)
+			.await
+	})
+}
+
+fn create_teams(
+	rosterfiles: Vec<RosterFile>,
+	source: PathBuf,
+	destination: PathBuf,
+) -> impl Straw<(), Progress, ToolError> {
+	sipper(async move |mut progress| {
 		// I guess getting the files count as 5%.
 		let fraction_per_file = 95.0 / rosterfiles.len() as f32;
 		for (index, roster_file) in rosterfiles.iter().enumerate() {
@@ -53,7 +93,7 @@ pub fn create_team_and_portraits(
 				})
 				.await;
 
-			let roster = Roster::from_rosterfile(&roster_file).await?;
+			let roster = Roster::from_rosterfile(roster_file).await?;
 			convert_portraits(&roster_file.team, &roster, &source, &destination).await?;
 			create_team_file(&roster_file.team, roster, &destination, FormatType::TOML).await?;
 		}
@@ -62,6 +102,11 @@ pub fn create_team_and_portraits(
 	})
 }
 
+// Folder with the team's portraits, as exported by the game.
+pub fn dds_folder(team: &str, source: &Path) -> PathBuf {
+	source.join(format!("{}_dds", slugify(team)))
+}
+
 pub async fn create_team_file(
 	team: &str,
 	mut roster: Roster,
@@ -102,8 +147,7 @@ async fn convert_portraits(
 	source: &Path,
 	destination: &Path,
 ) -> Result<(), ToolError> {
-	let dds_relative_name = format!("{}_dds", slugify(team));
-	let dds_folder = source.join(&dds_relative_name);
+	let dds_folder = dds_folder(team, source);
 	if !dds_folder.is_dir() {
 		return Err(CreationError::CouldNotCreateFolder(format!(
 			"Can't rename portraits because the dds folder '{}' doesn't exist.",
@@ -125,7 +169,7 @@ async fn convert_portraits(
 		// Convert .dds (e.g. "player_XXX03.dds") to .png (e.g. "example-name.png").
 		// Converted portraits are placed in a separate folder.
 		let default_name = format!("player_XXX{:02}", player.id);
-		let dds_path = source.join(&dds_folder).join(format!("{default_name}.dds"));
+		let dds_path = dds_folder.join(format!("{default_name}.dds"));
 
 		if !dds_path.is_file() {
 			eprintln!(
@@ -1,6 +1,7 @@
 pub mod create;
 pub mod player;
 pub mod roster;
+pub mod watch;
 
 pub fn slugify(str: &str) -> String {
 	str.to_ascii_lowercase()
@@ -60,8 +60,7 @@ impl PlayerState {
 
 		let id =
 			if parts[0].trim_end().len() >= 5 && parts[0].to_ascii_lowercase().starts_with("xxx") {
-				str::parse::<u8>(&parts[0][3..=4])
-					.or_else(|_| return Err(PlayerError::InvalidID(s.clone())))?
+				str::parse::<u8>(&parts[0][3..=4]).map_err(|_| PlayerError::InvalidID(s.clone()))?
 			} else {
 				return Err(PlayerError::InvalidID(s).into());
 			};
@@ -105,7 +104,7 @@ impl PlayerState {
 			if p_name
 				.chars()
 				.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
-				&& p_name.len() > 0
+				&& !p_name.is_empty()
 			{
 				portrait_name = Some(p_name);
 			} else {
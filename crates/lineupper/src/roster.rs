@@ -29,7 +29,7 @@ impl Roster {
 				Ok(Some(line)) => match PlayerState::from_string(line) {
 					Ok(PlayerState::Active(p)) => active_players.push(p),
 					Ok(PlayerState::Reserve(p)) => reserve_players.push(p),
-					Err(ToolError::PlayerError(e)) if e == PlayerError::NotAPlayer => (),
+					Err(ToolError::PlayerError(PlayerError::NotAPlayer)) => (),
 					Err(e) => return Err(e),
 				},
 				Err(e) => {
@@ -61,10 +61,10 @@ impl Roster {
 	pub(crate) fn players(
 		&self,
 	) -> std::iter::Chain<std::slice::Iter<'_, Player>, std::slice::Iter<'_, Player>> {
-		(&self.active).iter().chain(&self.reserve)
+		self.active.iter().chain(&self.reserve)
 	}
 
-	pub(crate) fn sort<'a>(&mut self) {
+	pub(crate) fn sort(&mut self) {
 		self.active
 			.sort_by(|a, b| a.position.cmp(&b.position).then(a.name.cmp(&b.name)));
 		self.reserve
@@ -90,7 +90,7 @@ impl Roster {
 			})
 			.collect();
 
-		roster.sort_by(|(_, a), (_, b)| a.cmp(b));
+		roster.sort_by_key(|(_, a)| *a);
 		let msrf_strings: Vec<String> = roster.into_iter().map(|(s, _)| s).collect();
 		let msrf_header = format!("---{team}---\n\nCURRENT LINE-UP:\n\n");
 
@@ -107,10 +107,10 @@ impl RosterFile {
 	pub async fn get_rosterfile(path: PathBuf) -> Result<RosterFile, ToolError> {
 		let file_extension = Path::new(&path)
 			.extension()
-			.ok_or_else(|| RosterFileError::NotARosterFile)?
+			.ok_or(RosterFileError::NotARosterFile)?
 			.to_ascii_lowercase()
 			.into_string()
-			.map_err(|e| RosterFileError::InvalidExtension(e))?;
+			.map_err(RosterFileError::InvalidExtension)?;
 
 		if !path.is_file() || file_extension != "msrf" {
 			return Err(RosterFileError::NotARosterFile.into());
@@ -139,9 +139,7 @@ impl RosterFile {
 				Ok(None) => break,
 				Ok(Some(entry)) => match Self::get_rosterfile(entry.path()).await {
 					Ok(r) => rosterfiles.push(r),
-					Err(ToolError::RosterFileError(e)) if e == RosterFileError::NotARosterFile => {
-						()
-					}
+					Err(ToolError::RosterFileError(RosterFileError::NotARosterFile)) => {}
 					Err(e) => return Err(e),
 				},
 				Err(e) => return Err(e.into()),
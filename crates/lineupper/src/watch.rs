@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+use iced::task::{sipper, Sipper};
+use tokio::{fs, time};
+
+use common::{
+	watch::{Snapshot, POLL_INTERVAL},
+	WatchUpdate,
+};
+
+use crate::create::{create_team_and_portraits, recreate_changed_teams};
+
+// Creates the teams and portraits, then creates them again every time a roster file or
+// portrait changes, until it's dropped. Only the teams with changed files are created
+// again, unless the previous run failed.
+pub fn watch_rosters(source: PathBuf, destination: PathBuf) -> impl Sipper<(), WatchUpdate> {
+	sipper(async move |mut updates| {
+		let mut snapshot: Option<Snapshot> = None;
+		let mut failed = false;
+		let mut run = 0;
+		loop {
+			let new_snapshot = Snapshot::of(input_files(&source).await).await;
+			let result = match &snapshot {
+				Some(previous) if *previous == new_snapshot => None,
+				Some(previous) if !failed => Some(
+					recreate_changed_teams(
+						source.clone(),
+						destination.clone(),
+						new_snapshot.changed_since(previous),
+					)
+					.with(WatchUpdate::Working)
+					.run(&updates)
+					.await,
+				),
+				// The first run, or a retry of everything that failed last time.
+				_ => Some(
+					create_team_and_portraits(source.clone(), destination.clone())
+						.with(WatchUpdate::Working)
+						.run(&updates)
+						.await,
+				),
+			};
+			snapshot = Some(new_snapshot);
+
+			if let Some(result) = result {
+				run += 1;
+				failed = result.is_err();
+				updates.send(WatchUpdate::Finished { run, result }).await;
+			}
+			time::sleep(POLL_INTERVAL).await;
+		}
+	})
+}
+
+// The files in the source folder, its portrait folders and what's in them. Other folders are
+// left out, so a destination inside the source folder doesn't set off another run.
+async fn input_files(source: &Path) -> Vec<PathBuf> {
+	let mut files = Vec::new();
+	let Ok(mut entries) = fs::read_dir(source).await else {
+		return files;
+	};
+	while let Ok(Some(entry)) = entries.next_entry().await {
+		let path = entry.path();
+		if !path.is_dir() {
+			files.push(path);
+		} else if path.to_string_lossy().ends_with("_dds") {
+			files.push(path.clone());
+			if let Ok(mut portraits) = fs::read_dir(&path).await {
+				while let Ok(Some(portrait)) = portraits.next_entry().await {
+					files.push(portrait.path());
+				}
+			}
+		}
+	}
+	files
+}
@@ -1,5 +1,3 @@
-use winres;
-
 fn main() {
 	if cfg!(target_os = "windows") {
 		let mut res = winres::WindowsResource::new();
@@ -0,0 +1,74 @@
+// Command line version of the tools, for running them without the GUI.
+
+use std::{pin::pin, process::ExitCode};
+
+use iced::{futures::StreamExt, task::Sipper};
+use tokio::runtime::Runtime;
+
+use common::{errors::ToolError, WatchUpdate};
+use statter::serve::{serve, DEFAULT_PORT};
+
+const USAGE: &str = "Usage:
+  multitool-cli lineupper <source> <destination> [--watch]
+  multitool-cli statter <source> <destination> [--watch]
+  multitool-cli serve <source> [port]";
+
+fn main() -> ExitCode {
+	let args: Vec<String> = std::env::args().skip(1).collect();
+	let runtime = Runtime::new().unwrap();
+
+	let result: Result<(), ToolError> = match args.iter().map(String::as_str).collect::<Vec<_>>()[..]
+	{
+		["lineupper", source, destination] => runtime.block_on(
+			lineupper::create::create_team_and_portraits(source.into(), destination.into()),
+		),
+		["statter", source, destination] => runtime.block_on(statter::entry::run_tournaments(
+			source.into(),
+			destination.into(),
+		)),
+		["lineupper", source, destination, "--watch"] => runtime.block_on(watch(
+			lineupper::watch::watch_rosters(source.into(), destination.into()),
+		)),
+		["statter", source, destination, "--watch"] => runtime.block_on(watch(
+			statter::watch::watch_tournaments(source.into(), destination.into()),
+		)),
+		["serve", source] => runtime.block_on(serve(source.into(), DEFAULT_PORT)),
+		["serve", source, port] => match port.parse() {
+			Ok(port) => runtime.block_on(serve(source.into(), port)),
+			Err(_) => {
+				eprintln!("'{port}' isn't a valid port.");
+				return ExitCode::FAILURE;
+			}
+		},
+		_ => {
+			eprintln!("{USAGE}");
+			return ExitCode::FAILURE;
+		}
+	};
+
+	match result {
+		Ok(()) => {
+			println!("Done.");
+			ExitCode::SUCCESS
+		}
+		Err(e) => {
+			eprintln!("Error: {e}");
+			ExitCode::FAILURE
+		}
+	}
+}
+
+// Prints the outcome of every run. Keeps going until the process is stopped.
+async fn watch(updates: impl Sipper<(), WatchUpdate>) -> Result<(), ToolError> {
+	println!("Watching for changes. Press Ctrl+C to stop.");
+	let mut updates = pin!(updates);
+	while let Some(update) = updates.next().await {
+		if let WatchUpdate::Finished { run, result } = update {
+			match result {
+				Ok(()) => println!("Run {run} succeeded."),
+				Err(e) => eprintln!("Run {run} failed: {e}"),
+			}
+		}
+	}
+	Ok(())
+}
@@ -29,5 +29,7 @@ pub enum Message {
 	BrowseSource(Tool),
 	BrowseDestination(Tool),
 	RunTool(Tool),
+	WatchTool(Tool),
+	StopTool(Tool),
 	UpdateTool(Tool, WorkUpdate),
 }
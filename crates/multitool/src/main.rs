@@ -185,7 +185,7 @@ impl Application {
 									&self.roster_editor.rows,
 									self.roster_editor.captain,
 								),
-								&save_path.parent().unwrap(),
+								save_path.parent().unwrap(),
 								format_type,
 							)) {
 								Messenger::error_message("Export Error", &e.to_string());
@@ -218,18 +218,7 @@ impl Application {
 				Task::none()
 			}
 			Message::RunTool(tool) => {
-				let (source, destination) = match tool {
-					Tool::LineUpper => (
-						self.tools.lineupper.source.as_ref(),
-						self.tools.lineupper.destination.as_ref(),
-					),
-					Tool::Statter => (
-						self.tools.statter.source.as_ref(),
-						self.tools.statter.destination.as_ref(),
-					),
-				};
-
-				let task = if let (Some(source), Some(destination)) = (source, destination) {
+				let task = if let (Some(source), Some(destination)) = self.tools.paths(tool) {
 					self.tools.start(tool, source.clone(), destination.clone())
 				} else {
 					Messenger::info_message(
@@ -240,6 +229,22 @@ impl Application {
 				};
 				task.map(Message::UpdateTool.with(tool))
 			}
+			Message::WatchTool(tool) => {
+				let task = if let (Some(source), Some(destination)) = self.tools.paths(tool) {
+					self.tools.watch(tool, source.clone(), destination.clone())
+				} else {
+					Messenger::info_message(
+						"Watch Error",
+						"A source or destination folder wasn't selected.",
+					);
+					return Task::none();
+				};
+				task.map(Message::UpdateTool.with(tool))
+			}
+			Message::StopTool(tool) => {
+				self.tools.stop(tool);
+				Task::none()
+			}
 			Message::UpdateTool(tool, update) => {
 				self.tools.update(tool, update);
 				Task::none()
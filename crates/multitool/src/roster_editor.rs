@@ -72,7 +72,7 @@ impl RosterEditor {
 		};
 
 		let table_columns = [
-			table::column(bold("ID"), |row: &RosterRow| text(&row.id))
+			table::column(bold("ID"), |row: &RosterRow| text(row.id))
 				.align_x(Center)
 				.align_y(Center),
 			table::column(bold("Name"), |row: &RosterRow| {
@@ -145,8 +145,8 @@ impl RosterEditor {
 impl Default for RosterEditor {
 	fn default() -> Self {
 		let mut rows: [RosterRow; 23] = Default::default();
-		for x in 0..23 {
-			rows[x].id = x as u8 + 1;
+		for (x, row) in rows.iter_mut().enumerate() {
+			row.id = x as u8 + 1;
 		}
 
 		Self {
@@ -218,12 +218,12 @@ impl RosterRow {
 		let mut roster: Vec<RosterRow> = roster
 			.active
 			.into_iter()
-			.chain(roster.reserve.into_iter())
-			.map(|p| RosterRow::from_player(p))
+			.chain(roster.reserve)
+			.map(RosterRow::from_player)
 			.collect();
 
 		// Sort them by the ID.
-		roster.sort_by(|a, b| a.id.cmp(&b.id));
+		roster.sort_by_key(|a| a.id);
 		let roster = roster.try_into().unwrap_or_else(|v: Vec<RosterRow>| {
 			panic!("Expected Roster of 23 players, found {}", v.len())
 		});
@@ -1,6 +1,6 @@
 use std::{ffi::OsStr, path::PathBuf};
 
-use common::{errors::ToolError, Progress};
+use common::{errors::ToolError, Progress, WatchUpdate};
 use iced::{
 	font, task,
 	widget::{button, column, progress_bar, row, text},
@@ -34,12 +34,19 @@ enum WorkState {
 	},
 	Finished,
 	Errored,
+	// Runs the tool again whenever its source files change, until stopped.
+	Watching {
+		progress: f32,
+		last_run: Option<(u32, Result<(), ToolError>)>,
+		_task: task::Handle,
+	},
 }
 
 #[derive(Debug, Clone)]
 pub enum WorkUpdate {
 	Working(Progress),
 	Finished(Result<(), ToolError>),
+	Watching(WatchUpdate),
 }
 
 #[derive(Default)]
@@ -81,18 +88,69 @@ impl Tools {
 
 				task
 			}
-			WorkState::Working { .. } => Task::none(),
+			WorkState::Working { .. } | WorkState::Watching { .. } => Task::none(),
 		}
 	}
 
+	pub fn watch(&mut self, tool: Tool, source: PathBuf, destination: PathBuf) -> Task<WorkUpdate> {
+		let tool_state = match tool {
+			Tool::LineUpper => &mut self.lineupper,
+			Tool::Statter => &mut self.statter,
+		};
+		match tool_state.state {
+			WorkState::Idle | WorkState::Finished | WorkState::Errored => {
+				let (task, handle) = match tool {
+					Tool::LineUpper => Task::run(
+						lineupper::watch::watch_rosters(source, destination),
+						WorkUpdate::Watching,
+					)
+					.abortable(),
+					Tool::Statter => Task::run(
+						statter::watch::watch_tournaments(source, destination),
+						WorkUpdate::Watching,
+					)
+					.abortable(),
+				};
+
+				tool_state.state = WorkState::Watching {
+					progress: 0.0,
+					last_run: None,
+					_task: handle.abort_on_drop(),
+				};
+
+				task
+			}
+			WorkState::Working { .. } | WorkState::Watching { .. } => Task::none(),
+		}
+	}
+
+	pub fn stop(&mut self, tool: Tool) {
+		let work_state = match tool {
+			Tool::LineUpper => &mut self.lineupper.state,
+			Tool::Statter => &mut self.statter.state,
+		};
+		// Dropping the handle stops the watch task.
+		if let WorkState::Watching { .. } = work_state {
+			*work_state = WorkState::Idle;
+		}
+	}
+
+	pub fn paths(&self, tool: Tool) -> (Option<&PathBuf>, Option<&PathBuf>) {
+		let tool_state = match tool {
+			Tool::LineUpper => &self.lineupper,
+			Tool::Statter => &self.statter,
+		};
+		(tool_state.source.as_ref(), tool_state.destination.as_ref())
+	}
+
 	pub fn update(&mut self, tool: Tool, update: WorkUpdate) {
 		let work_state = match tool {
 			Tool::LineUpper => &mut self.lineupper.state,
 			Tool::Statter => &mut self.statter.state,
 		};
 
-		if let WorkState::Working { progress, .. } = work_state {
-			match update {
+		match work_state {
+			WorkState::Working { progress, .. } => match update {
 				WorkUpdate::Working(new_progress) => *progress = new_progress.percent,
 				WorkUpdate::Finished(result) => {
 					*work_state = match result {
@@ -103,7 +161,22 @@ impl Tools {
 						}
 					};
 				}
-			}
+				WorkUpdate::Watching(_) => {}
+			},
+			// Errors are shown next to the buttons instead of in a dialog, since another
+			// one could pop up on every save.
+			WorkState::Watching {
+				progress, last_run, ..
+			} => match update {
+				WorkUpdate::Watching(WatchUpdate::Working(new_progress)) => {
+					*progress = new_progress.percent
+				}
+				WorkUpdate::Watching(WatchUpdate::Finished { run, result }) => {
+					*last_run = Some((run, result))
+				}
+				WorkUpdate::Working(_) | WorkUpdate::Finished(_) => {}
+			},
+			WorkState::Idle | WorkState::Finished | WorkState::Errored => {}
 		}
 	}
 
@@ -171,6 +244,9 @@ impl Tools {
 			.style(button::success)
 			.on_press(Message::RunTool(tool));
 
+		let mut watch_button = button("Watch").on_press(Message::WatchTool(tool));
+		let mut run_error = None;
+
 		let (progress, tool_status_text) = match work_state {
 			WorkState::Idle | WorkState::Errored
 				if source_path.is_some() && destination_path.is_some() =>
@@ -179,24 +255,46 @@ impl Tools {
 			}
 			WorkState::Idle | WorkState::Errored => {
 				run_button = button("Run!").style(button::success);
+				watch_button = button("Watch");
 				(0.0, "Missing path(s).".to_string())
 			}
 			WorkState::Working { progress, _task } => (progress, format!("{progress:.2}%")),
 			WorkState::Finished => (100.0, "Done!".to_string()),
+			WorkState::Watching {
+				progress,
+				last_run,
+				_task,
+			} => {
+				run_button = button("Run!").style(button::success);
+				watch_button = button("Stop")
+					.style(button::danger)
+					.on_press(Message::StopTool(tool));
+				let status = match last_run {
+					None => "Watching...".to_string(),
+					Some((run, Ok(()))) => format!("Watching. Run {run} succeeded."),
+					Some((run, Err(e))) => {
+						run_error = Some(e.to_string());
+						format!("Watching. Run {run} failed:")
+					}
+				};
+				(progress, status)
+			}
 		};
 
-		let button_row = row![run_button, text(tool_status_text),]
+		let button_row = row![run_button, watch_button, text(tool_status_text),]
 			.align_y(Center)
 			.spacing(MARGIN * 2.0);
 
-		column![
+		let mut section = column![
 			header.align_y(Center),
 			browse_source,
 			browse_destination,
 			progress_bar(0.0..=100.0, progress),
 			button_row,
-		]
-		.spacing(MARGIN * 2.0)
-		.into()
+		];
+		if let Some(run_error) = run_error {
+			section = section.push(text(run_error).style(text::danger));
+		}
+		section.spacing(MARGIN * 2.0).into()
 	}
 }
@@ -0,0 +1,402 @@
+use serde::{Deserialize, Serialize};
+
+use crate::fixture::Fixture;
+use crate::team::TeamPlacement;
+use crate::tournament::{GroupTable, Tournament};
+use common::TeamName;
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, strum_macros::Display)]
+pub enum Stage {
+	Groups,
+	Winners,
+	Losers,
+	#[strum(to_string = "Grand Final")]
+	GrandFinal,
+}
+
+impl Stage {
+	pub fn abbreviation(&self) -> &'static str {
+		match self {
+			Stage::Groups => "Groups",
+			Stage::Winners => "WB",
+			Stage::Losers => "LB",
+			Stage::GrandFinal => "GF",
+		}
+	}
+}
+
+// Fixtures of one round of a bracket, or of one group.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Round {
+	pub stage: Stage,
+	pub name: String,
+	pub fixtures: Vec<Fixture>,
+}
+
+impl Round {
+	// Every fixture in the tournament, grouped by stage and round. Expects the tournament
+	// to have run successfully, so the bracket sizes are known to be valid.
+	pub fn all_from(tournament: &Tournament) -> Vec<Round> {
+		let mut rounds = Vec::new();
+
+		if let Some(groups) = &tournament.brackets.groups {
+			let mut group_ids: Vec<_> = groups.iter().filter_map(|f| f.group).collect();
+			group_ids.sort_unstable();
+			group_ids.dedup();
+			for group in group_ids {
+				rounds.push(Round {
+					stage: Stage::Groups,
+					name: format!("Group {group}"),
+					fixtures: groups
+						.iter()
+						.filter(|f| f.group == Some(group))
+						.cloned()
+						.collect(),
+				});
+			}
+		}
+
+		let winners = &tournament.brackets.winners;
+		let mut fixtures_taken = 0;
+		for (fixture_count, name) in winners_rounds(tournament.playoff_teams, winners.len()) {
+			rounds.push(Round {
+				stage: Stage::Winners,
+				name,
+				fixtures: winners[fixtures_taken..fixtures_taken + fixture_count].to_vec(),
+			});
+			fixtures_taken += fixture_count;
+		}
+
+		if let Some(losers) = &tournament.brackets.losers {
+			let mut fixtures_taken = 0;
+			for (fixture_count, name) in losers_rounds(losers.len()) {
+				rounds.push(Round {
+					stage: Stage::Losers,
+					name,
+					fixtures: losers[fixtures_taken..fixtures_taken + fixture_count].to_vec(),
+				});
+				fixtures_taken += fixture_count;
+			}
+		}
+
+		if let Some(grand_final) = &tournament.grand_final {
+			rounds.push(Round {
+				stage: Stage::GrandFinal,
+				name: "Final".to_string(),
+				fixtures: grand_final.clone(),
+			});
+		}
+
+		rounds
+	}
+}
+
+// Same stage sizes as PlayoffStage::winners_bracket, but as (fixture count, round name).
+fn winners_rounds(playoff_teams: u8, fixture_count: usize) -> Vec<(usize, String)> {
+	let mut rounds = Vec::new();
+	let mut stages_left = f32::ceil(f32::log2(playoff_teams as f32)) as u32;
+	let mut stage_fixture_count =
+		playoff_teams as usize - 2usize.pow(f32::log2(playoff_teams as f32) as u32);
+	if stage_fixture_count == 0 {
+		stage_fixture_count = playoff_teams as usize / 2;
+	}
+
+	let mut fixtures_left = fixture_count;
+	while fixtures_left > 0 && stages_left > 0 {
+		let name = match stages_left {
+			1 => "Final".to_string(),
+			2 => "SF".to_string(),
+			3 => "QF".to_string(),
+			n => format!("R{}", 2usize.pow(n)),
+		};
+		let stage_fixture_count_capped = stage_fixture_count.min(fixtures_left);
+		rounds.push((stage_fixture_count_capped, name));
+		fixtures_left -= stage_fixture_count_capped;
+
+		stages_left -= 1;
+		stage_fixture_count = 2usize.pow(stages_left) / 2;
+	}
+	rounds
+}
+
+// Same stage sizes as PlayoffStage::losers_bracket, but as (fixture count, round name).
+fn losers_rounds(fixture_count: usize) -> Vec<(usize, String)> {
+	if fixture_count == 0 {
+		return Vec::new();
+	}
+
+	let stage_fixture_count = |stage: u8| 2usize.pow(f32::ceil(stage as f32 / 2.0 - 1.0) as u32);
+	let stages_fixtures_accum = |stages: u8| {
+		(2usize.pow(f32::ceil(stages as f32 / 2.0) as u32) - 1) * 2
+			- stages as usize % 2 * stage_fixture_count(stages)
+	};
+	let mut stages_left = 0;
+	while stages_fixtures_accum(stages_left) < fixture_count {
+		stages_left += 1;
+	}
+	let total_stages = stages_left;
+
+	let mut rounds = Vec::new();
+	let mut stage_fixtures = fixture_count - stages_fixtures_accum(stages_left - 1);
+	let mut fixtures_left = fixture_count;
+	while fixtures_left > 0 && stages_left > 0 {
+		let name = match stages_left {
+			1 => "Final".to_string(),
+			n => format!("R{}", total_stages - n + 1),
+		};
+		let stage_fixtures_capped = stage_fixtures.min(fixtures_left);
+		rounds.push((stage_fixtures_capped, name));
+		fixtures_left -= stage_fixtures_capped;
+
+		stages_left -= 1;
+		stage_fixtures = stage_fixture_count(stages_left);
+	}
+	rounds
+}
+
+// A team's route through the tournament, e.g. "Groups B 2nd → WB SF L 1-2 → LB Final W 3-0".
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TeamPath {
+	pub team: TeamName,
+	pub path: String,
+}
+
+impl TeamPath {
+	pub fn all_from(
+		rounds: &[Round],
+		group_tables: Option<&Vec<GroupTable>>,
+		team_placements: &[TeamPlacement],
+	) -> Vec<TeamPath> {
+		team_placements
+			.iter()
+			.map(|tp| TeamPath {
+				team: tp.team.name,
+				path: Self::steps(tp.team.name, tp.placement, rounds, group_tables).join(" → "),
+			})
+			.collect()
+	}
+
+	fn steps(
+		team: TeamName,
+		placement: Option<u8>,
+		rounds: &[Round],
+		group_tables: Option<&Vec<GroupTable>>,
+	) -> Vec<String> {
+		let mut steps = Vec::new();
+
+		for table in group_tables.into_iter().flatten() {
+			if let Some(standing) = table.standings.iter().find(|s| s.team == team) {
+				steps.push(format!(
+					"Groups {} {}",
+					table.group,
+					ordinal(standing.position)
+				));
+			}
+		}
+
+		for round in rounds.iter().filter(|r| r.stage != Stage::Groups) {
+			for fixture in &round.fixtures {
+				let (goals_for, goals_against, pens_for, pens_against) = if fixture.team1 == team {
+					(fixture.score1, fixture.score2, fixture.pen1, fixture.pen2)
+				} else if fixture.team2 == team {
+					(fixture.score2, fixture.score1, fixture.pen2, fixture.pen1)
+				} else {
+					continue;
+				};
+
+				let outcome = match fixture.winner() {
+					Ok(Some(winner)) if winner == team => "W",
+					Ok(Some(_)) => "L",
+					_ => "D",
+				};
+				let mut step = format!(
+					"{} {} {outcome} {goals_for}-{goals_against}",
+					round.stage.abbreviation(),
+					round.name
+				);
+				if let (Some(pf), Some(pa)) = (pens_for, pens_against) {
+					step += &format!(" ({pf}-{pa} p)");
+				}
+				steps.push(step);
+			}
+		}
+
+		if let Some(placement) = placement {
+			steps.push(ordinal(placement));
+		}
+
+		steps
+	}
+}
+
+pub fn ordinal(n: u8) -> String {
+	let suffix = match (n % 10, n % 100) {
+		(_, 11..=13) => "th",
+		(1, _) => "st",
+		(2, _) => "nd",
+		(3, _) => "rd",
+		_ => "th",
+	};
+	format!("{n}{suffix}")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_support::{self, cup, fixture};
+	use crate::tournament::TournamentResult;
+
+	fn run(playoff_teams: u8, has_losers: bool, fixtures: &[String]) -> TournamentResult {
+		test_support::run(&cup(playoff_teams, has_losers, fixtures))
+	}
+
+	fn round_names(result: &TournamentResult) -> Vec<String> {
+		result
+			.rounds
+			.iter()
+			.map(|r| {
+				format!(
+					"{} {} ({})",
+					r.stage.abbreviation(),
+					r.name,
+					r.fixtures.len()
+				)
+			})
+			.collect()
+	}
+
+	fn path(result: &TournamentResult, team: TeamName) -> &str {
+		&result.paths.iter().find(|p| p.team == team).unwrap().path
+	}
+
+	fn eight_team_winners() -> Vec<String> {
+		let winners = "brackets.winners";
+		vec![
+			fixture(winners, "Cursed", "Moai", 4, 0),
+			fixture(winners, "Vidya", "Autoism", 1, 0),
+			fixture(winners, "Gambit", "Disney", 3, 0),
+			fixture(winners, "Legoland", "Bone Zone", 2, 0),
+			fixture(winners, "Cursed", "Vidya", 2, 1),
+			fixture(winners, "Gambit", "Legoland", 1, 0),
+			fixture(winners, "Cursed", "Gambit", 1, 0),
+		]
+	}
+
+	#[test]
+	fn four_teams() {
+		let winners = "brackets.winners";
+		let result = run(
+			4,
+			false,
+			&[
+				fixture(winners, "Cursed", "Moai", 3, 0),
+				fixture(winners, "Vidya", "Autoism", 1, 0),
+				fixture(winners, "Cursed", "Vidya", 2, 1),
+			],
+		);
+
+		assert_eq!(round_names(&result), ["WB SF (2)", "WB Final (1)"]);
+		assert_eq!(
+			path(&result, TeamName::Cursed),
+			"WB SF W 3-0 → WB Final W 2-1 → 1st"
+		);
+		assert_eq!(path(&result, TeamName::Moai), "WB SF L 0-3 → 4th");
+	}
+
+	#[test]
+	fn four_teams_with_losers_and_grand_final_reset() {
+		let (winners, losers) = ("brackets.winners", "brackets.losers");
+		let result = run(
+			4,
+			true,
+			&[
+				fixture(winners, "Cursed", "Moai", 3, 0),
+				fixture(winners, "Vidya", "Autoism", 1, 0),
+				fixture(winners, "Cursed", "Vidya", 2, 1),
+				fixture(losers, "Moai", "Autoism", 2, 0),
+				fixture(losers, "Vidya", "Moai", 1, 0),
+				fixture("grand_final", "Vidya", "Cursed", 1, 0),
+				fixture("grand_final", "Cursed", "Vidya", 2, 0),
+			],
+		);
+
+		assert_eq!(
+			round_names(&result),
+			[
+				"WB SF (2)",
+				"WB Final (1)",
+				"LB R1 (1)",
+				"LB Final (1)",
+				"GF Final (2)"
+			]
+		);
+		assert_eq!(
+			path(&result, TeamName::Cursed),
+			"WB SF W 3-0 → WB Final W 2-1 → GF Final L 0-1 → GF Final W 2-0 → 1st"
+		);
+		assert_eq!(
+			path(&result, TeamName::Vidya),
+			"WB SF W 1-0 → WB Final L 1-2 → LB Final W 1-0 → GF Final W 1-0 → GF Final L 0-2 → 2nd"
+		);
+		assert_eq!(
+			path(&result, TeamName::Moai),
+			"WB SF L 0-3 → LB R1 W 2-0 → LB Final L 0-1 → 3rd"
+		);
+	}
+
+	#[test]
+	fn eight_teams() {
+		let result = run(8, false, &eight_team_winners());
+
+		assert_eq!(
+			round_names(&result),
+			["WB QF (4)", "WB SF (2)", "WB Final (1)"]
+		);
+		assert_eq!(
+			path(&result, TeamName::Cursed),
+			"WB QF W 4-0 → WB SF W 2-1 → WB Final W 1-0 → 1st"
+		);
+		assert_eq!(
+			path(&result, TeamName::Legoland),
+			"WB QF W 2-0 → WB SF L 0-1 → 3rd"
+		);
+	}
+
+	#[test]
+	fn eight_teams_with_losers() {
+		let losers = "brackets.losers";
+		let mut fixtures = eight_team_winners();
+		fixtures.extend([
+			fixture(losers, "Moai", "Autoism", 1, 0),
+			fixture(losers, "Disney", "Bone Zone", 1, 0),
+			fixture(losers, "Vidya", "Moai", 1, 0),
+			fixture(losers, "Legoland", "Disney", 1, 0),
+			fixture(losers, "Vidya", "Legoland", 1, 0),
+			fixture(losers, "Gambit", "Vidya", 1, 0),
+			fixture("grand_final", "Cursed", "Gambit", 2, 0),
+		]);
+		let result = run(8, true, &fixtures);
+
+		assert_eq!(
+			round_names(&result),
+			[
+				"WB QF (4)",
+				"WB SF (2)",
+				"WB Final (1)",
+				"LB R1 (2)",
+				"LB R2 (2)",
+				"LB R3 (1)",
+				"LB Final (1)",
+				"GF Final (1)"
+			]
+		);
+		assert_eq!(
+			path(&result, TeamName::Gambit),
+			"WB QF W 3-0 → WB SF W 1-0 → WB Final L 0-1 → LB Final W 1-0 → GF Final L 0-2 → 2nd"
+		);
+		assert_eq!(
+			path(&result, TeamName::Moai),
+			"WB QF L 0-4 → LB R1 W 1-0 → LB R2 L 0-1 → 6th"
+		);
+	}
+}
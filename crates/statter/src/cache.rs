@@ -0,0 +1,161 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::players::PlayerIdentities;
+use crate::tournament::TournamentResult;
+use common::errors::ToolError;
+
+// Caches from another release are thrown out, since results may be computed differently.
+// Between releases, bump this whenever TournamentResult, Team or anything they hold changes
+// shape, or a cup would be run differently.
+const CACHE_SCHEMA: u32 = 12;
+const CACHE_FILENAME: &str = ".statter-cache.toml";
+
+fn cache_version() -> String {
+	format!("{}+{CACHE_SCHEMA}", env!("CARGO_PKG_VERSION"))
+}
+
+// Keyed on content only, so the same cups give the same cache wherever they're run from.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct CachedCup {
+	pub hash: String,
+	pub result: TournamentResult,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct CupCache {
+	version: String,
+	cups: Vec<CachedCup>,
+}
+
+impl CupCache {
+	pub fn new() -> Self {
+		Self {
+			version: cache_version(),
+			cups: Vec::new(),
+		}
+	}
+
+	// A missing, unreadable or outdated cache is treated as empty, which just means
+	// every cup gets recomputed.
+	pub async fn load(destination: &Path) -> Self {
+		let cache_path = destination.join(CACHE_FILENAME);
+		if !cache_path.is_file() {
+			return Self::new();
+		}
+
+		match fs::read_to_string(&cache_path).await {
+			Ok(s) => match toml::from_str::<CupCache>(&s) {
+				Ok(cache) if cache.version == cache_version() => cache,
+				_ => Self::new(),
+			},
+			Err(_) => Self::new(),
+		}
+	}
+
+	pub async fn save(&self, destination: &Path) -> Result<(), ToolError> {
+		let cache_toml = toml::to_string(&self)?;
+		fs::write(destination.join(CACHE_FILENAME), cache_toml).await?;
+		Ok(())
+	}
+
+	pub fn get(&self, hash: &str) -> Option<&CachedCup> {
+		self.cups.iter().find(|c| c.hash == hash)
+	}
+
+	pub fn push(&mut self, cup: CachedCup) {
+		self.cups.push(cup);
+	}
+}
+
+impl Default for CupCache {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+// Key of a cup in the cache. Resolved player names depend on the identities too, so they're
+// part of it.
+pub fn cup_key(cup_string: &str, identities: &PlayerIdentities) -> String {
+	content_hash((cup_string.to_string() + identities.fingerprint()).as_bytes())
+}
+
+// FNV-1a, since std's hasher isn't guaranteed to be stable between Rust versions.
+fn content_hash(bytes: &[u8]) -> String {
+	const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+	const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+	let hash = bytes.iter().fold(FNV_OFFSET, |hash, &b| {
+		(hash ^ b as u64).wrapping_mul(FNV_PRIME)
+	});
+	format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_support::{block_on, cup, fixture, identities, run, TempDir};
+
+	fn test_cup() -> String {
+		cup(
+			2,
+			false,
+			&[fixture("brackets.winners", "Cursed", "Moai", 2, 0)],
+		)
+	}
+
+	fn cache_with(key: &str) -> CupCache {
+		let mut cache = CupCache::new();
+		cache.push(CachedCup {
+			hash: key.to_string(),
+			result: run(&test_cup()),
+		});
+		cache
+	}
+
+	#[test]
+	fn unchanged_cup_hits() {
+		let identities = PlayerIdentities::default();
+		let cache = cache_with(&cup_key(&test_cup(), &identities));
+		assert!(cache.get(&cup_key(&test_cup(), &identities)).is_some());
+	}
+
+	#[test]
+	fn changed_cup_misses() {
+		let identities = PlayerIdentities::default();
+		let cache = cache_with(&cup_key(&test_cup(), &identities));
+		let changed = test_cup().replace("score1 = 2", "score1 = 3");
+		assert!(cache.get(&cup_key(&changed, &identities)).is_none());
+	}
+
+	#[test]
+	fn changed_identities_miss() {
+		let before = identities("[[players]]\nid = \"Scorer\"\n").unwrap();
+		let after = identities("[[players]]\nid = \"Scorer\"\naliases = [\"Toni\"]\n").unwrap();
+		let cache = cache_with(&cup_key(&test_cup(), &before));
+		assert!(cache.get(&cup_key(&test_cup(), &before)).is_some());
+		assert!(cache.get(&cup_key(&test_cup(), &after)).is_none());
+	}
+
+	#[test]
+	fn saved_cache_loads_again() {
+		let folder = TempDir::new();
+		let key = cup_key(&test_cup(), &PlayerIdentities::default());
+		block_on(cache_with(&key).save(folder.path())).unwrap();
+		let loaded = block_on(CupCache::load(folder.path()));
+		assert!(loaded.get(&key).is_some());
+	}
+
+	#[test]
+	fn version_bump_misses() {
+		let folder = TempDir::new();
+		let key = cup_key(&test_cup(), &PlayerIdentities::default());
+		let mut cache = cache_with(&key);
+		cache.version = format!("0.0.0+{CACHE_SCHEMA}");
+		block_on(cache.save(folder.path())).unwrap();
+		let loaded = block_on(CupCache::load(folder.path()));
+		assert!(loaded.get(&key).is_none());
+	}
+}
@@ -0,0 +1,416 @@
+use std::path::{Path, PathBuf};
+
+use image::{Rgb, RgbImage};
+use tokio::task;
+
+use crate::rankings::Seasons;
+use crate::tournament::TournamentResult;
+use common::{
+	errors::{EntryError, ToolError},
+	TeamName,
+};
+
+const WIDTH: u32 = 960;
+const HEIGHT: u32 = 540;
+const PLOT_LEFT: i32 = 70;
+const PLOT_RIGHT: i32 = WIDTH as i32 - 240;
+const PLOT_TOP: i32 = 60;
+const PLOT_BOTTOM: i32 = HEIGHT as i32 - 60;
+
+const BACKGROUND: Rgb<u8> = Rgb([255, 255, 255]);
+const GRID: Rgb<u8> = Rgb([225, 225, 225]);
+const AXIS: Rgb<u8> = Rgb([90, 90, 90]);
+
+// Indexed by TeamName, so a team has the same colour in every chart.
+const TEAM_COLOURS: [Rgb<u8>; 20] = [
+	Rgb([128, 128, 128]),
+	Rgb([31, 119, 180]),
+	Rgb([255, 127, 14]),
+	Rgb([44, 160, 44]),
+	Rgb([214, 39, 40]),
+	Rgb([148, 103, 189]),
+	Rgb([140, 86, 75]),
+	Rgb([227, 119, 194]),
+	Rgb([188, 189, 34]),
+	Rgb([23, 190, 207]),
+	Rgb([0, 0, 128]),
+	Rgb([170, 110, 40]),
+	Rgb([0, 128, 128]),
+	Rgb([128, 0, 0]),
+	Rgb([70, 240, 140]),
+	Rgb([240, 50, 230]),
+	Rgb([60, 60, 60]),
+	Rgb([128, 128, 0]),
+	Rgb([255, 180, 0]),
+	Rgb([100, 160, 255]),
+];
+
+pub fn team_colour(team: TeamName) -> Rgb<u8> {
+	TEAM_COLOURS[team as usize % TEAM_COLOURS.len()]
+}
+
+// One team's line. None where the team hadn't played yet that season.
+struct Series {
+	team: TeamName,
+	values: Vec<Option<u32>>,
+}
+
+// Writes a bump chart of ranks and a line chart of cumulative ranking points per season, e.g.
+// "season-2-ranks.png" and "season-2-points.png".
+pub async fn write_season_charts(
+	tourny_results: &[TournamentResult],
+	seasons: &Seasons,
+	destination: &Path,
+) -> Result<(), ToolError> {
+	for season in &seasons.seasons {
+		let tournament_count = season.tournaments.len();
+
+		let ranks: Vec<Series> = season
+			.rankings
+			.iter()
+			.map(|r| Series {
+				team: r.name,
+				values: r
+					.ranks
+					.iter()
+					.map(|&rank| (rank > 0).then_some(rank as u32))
+					.collect(),
+			})
+			.collect();
+
+		// Seasons only stores points for the tournaments a team played in, so cumulative
+		// points are counted again from the results.
+		let season_results: Vec<&TournamentResult> = tourny_results
+			.iter()
+			.filter(|tr| tr.season_num == season.season_num)
+			.collect();
+		let points: Vec<Series> = season
+			.rankings
+			.iter()
+			.map(|r| {
+				let mut total = None;
+				let values = season_results
+					.iter()
+					.map(|tr| {
+						if let Some(ranked) = tr
+							.get_teams_ranked()
+							.into_iter()
+							.find(|rt| rt.name == r.name)
+						{
+							total = Some(
+								total.unwrap_or(0) + ranked.ranking_points.last().unwrap_or(&0),
+							);
+						}
+						total
+					})
+					.collect();
+				Series {
+					team: r.name,
+					values,
+				}
+			})
+			.collect();
+
+		let max_rank = season.rankings.len().max(1) as u32;
+		let rank_chart = draw_chart(
+			&format!("Season {} ranks", season.season_num),
+			tournament_count,
+			&ranks,
+			max_rank,
+			true,
+		);
+		let rank_path = destination.join(format!("season-{}-ranks.png", season.season_num));
+		save_png(rank_chart, rank_path).await?;
+
+		let max_points = points
+			.iter()
+			.flat_map(|s| s.values.iter().flatten())
+			.max()
+			.copied()
+			.unwrap_or(0);
+		let points_chart = draw_chart(
+			&format!("Season {} points", season.season_num),
+			tournament_count,
+			&points,
+			nice_ceiling(max_points),
+			false,
+		);
+		let points_path = destination.join(format!("season-{}-points.png", season.season_num));
+		save_png(points_chart, points_path).await?;
+	}
+
+	Ok(())
+}
+
+// Encoding and writing a PNG blocks, so it's done off the async runtime.
+pub(crate) async fn save_png(img: RgbImage, path: PathBuf) -> Result<(), ToolError> {
+	let file = path.display().to_string();
+	task::spawn_blocking(move || img.save(path))
+		.await
+		.map_err(|e| EntryError::WriteTaskFailure(file, e.to_string()))??;
+	Ok(())
+}
+
+// Ranks go from 1 at the top to max_value at the bottom, points from 0 at the bottom to
+// max_value at the top.
+fn draw_chart(
+	title: &str,
+	tournament_count: usize,
+	series: &[Series],
+	max_value: u32,
+	is_rank: bool,
+) -> RgbImage {
+	let mut img = RgbImage::from_pixel(WIDTH, HEIGHT, BACKGROUND);
+	draw_text(&mut img, PLOT_LEFT, 20, title, 3, AXIS);
+
+	let min_value = if is_rank { 1 } else { 0 };
+	let span = (max_value - min_value).max(1) as i32;
+	let y_of = |value: u32| -> i32 {
+		let fraction = (value - min_value) as i32;
+		match is_rank {
+			true => PLOT_TOP + fraction * (PLOT_BOTTOM - PLOT_TOP) / span,
+			false => PLOT_BOTTOM - fraction * (PLOT_BOTTOM - PLOT_TOP) / span,
+		}
+	};
+	let x_of = |idx: usize| -> i32 {
+		match tournament_count {
+			0 | 1 => (PLOT_LEFT + PLOT_RIGHT) / 2,
+			n => PLOT_LEFT + idx as i32 * (PLOT_RIGHT - PLOT_LEFT) / (n as i32 - 1),
+		}
+	};
+
+	// Grid and axis labels.
+	let ticks: Vec<u32> = match is_rank {
+		true => (1..=max_value).collect(),
+		false => (0..=4).map(|i| max_value * i / 4).collect(),
+	};
+	for tick in ticks {
+		let y = y_of(tick);
+		draw_line(&mut img, (PLOT_LEFT, y), (PLOT_RIGHT, y), GRID, 0);
+		let label = tick.to_string();
+		let label_x = PLOT_LEFT - 10 - text_width(&label, 2);
+		draw_text(&mut img, label_x, y - 7, &label, 2, AXIS);
+	}
+	for idx in 0..tournament_count {
+		let x = x_of(idx);
+		draw_line(&mut img, (x, PLOT_TOP), (x, PLOT_BOTTOM), GRID, 0);
+		let label = (idx + 1).to_string();
+		draw_text(
+			&mut img,
+			x - text_width(&label, 2) / 2,
+			PLOT_BOTTOM + 12,
+			&label,
+			2,
+			AXIS,
+		);
+	}
+	let x_title = "Tournament";
+	let x_title_x = (PLOT_LEFT + PLOT_RIGHT) / 2 - text_width(x_title, 2) / 2;
+	draw_text(&mut img, x_title_x, PLOT_BOTTOM + 34, x_title, 2, AXIS);
+
+	// Lines, with a marker on every tournament.
+	for s in series {
+		let colour = team_colour(s.team);
+		let points: Vec<(usize, u32)> = s
+			.values
+			.iter()
+			.enumerate()
+			.filter_map(|(idx, v)| Some((idx, (*v)?)))
+			.collect();
+		for pair in points.windows(2) {
+			let from = (x_of(pair[0].0), y_of(pair[0].1));
+			let to = (x_of(pair[1].0), y_of(pair[1].1));
+			draw_line(&mut img, from, to, colour, 1);
+		}
+		for &(idx, value) in &points {
+			fill_circle(&mut img, (x_of(idx), y_of(value)), 4, colour);
+		}
+	}
+
+	// Legend, ordered like the final standings.
+	let mut legend: Vec<(TeamName, u32)> = series
+		.iter()
+		.filter_map(|s| Some((s.team, (*s.values.last()?)?)))
+		.collect();
+	match is_rank {
+		true => legend.sort_by_key(|&(team, value)| (value, team)),
+		false => legend.sort_by_key(|&(team, value)| (u32::MAX - value, team)),
+	}
+	for (i, (team, value)) in legend.into_iter().enumerate() {
+		let y = PLOT_TOP + i as i32 * 20;
+		let colour = team_colour(team);
+		fill_rect(&mut img, PLOT_RIGHT + 20, y, 12, 12, colour);
+		draw_text(
+			&mut img,
+			PLOT_RIGHT + 40,
+			y,
+			&format!("{value} {team}"),
+			2,
+			colour,
+		);
+	}
+
+	img
+}
+
+// Rounds up to a multiple of a power of ten, e.g. 1730 to 2000.
+fn nice_ceiling(value: u32) -> u32 {
+	if value == 0 {
+		return 4;
+	}
+	let magnitude = 10u32.pow(value.ilog10());
+	value.div_ceil(magnitude) * magnitude
+}
+
+fn put_pixel(img: &mut RgbImage, x: i32, y: i32, colour: Rgb<u8>) {
+	if x >= 0 && y >= 0 && (x as u32) < img.width() && (y as u32) < img.height() {
+		img.put_pixel(x as u32, y as u32, colour);
+	}
+}
+
+pub(crate) fn fill_rect(
+	img: &mut RgbImage,
+	x: i32,
+	y: i32,
+	width: i32,
+	height: i32,
+	colour: Rgb<u8>,
+) {
+	for dy in 0..height {
+		for dx in 0..width {
+			put_pixel(img, x + dx, y + dy, colour);
+		}
+	}
+}
+
+fn fill_circle(img: &mut RgbImage, (cx, cy): (i32, i32), radius: i32, colour: Rgb<u8>) {
+	for dy in -radius..=radius {
+		for dx in -radius..=radius {
+			if dx * dx + dy * dy <= radius * radius {
+				put_pixel(img, cx + dx, cy + dy, colour);
+			}
+		}
+	}
+}
+
+// Bresenham, with a square brush of 2 * thickness + 1 pixels.
+fn draw_line(
+	img: &mut RgbImage,
+	(mut x, mut y): (i32, i32),
+	(x1, y1): (i32, i32),
+	colour: Rgb<u8>,
+	thickness: i32,
+) {
+	let dx = (x1 - x).abs();
+	let dy = -(y1 - y).abs();
+	let sx = if x < x1 { 1 } else { -1 };
+	let sy = if y < y1 { 1 } else { -1 };
+	let mut err = dx + dy;
+	loop {
+		fill_rect(
+			img,
+			x - thickness,
+			y - thickness,
+			2 * thickness + 1,
+			2 * thickness + 1,
+			colour,
+		);
+		if x == x1 && y == y1 {
+			break;
+		}
+		let e2 = 2 * err;
+		if e2 >= dy {
+			err += dy;
+			x += sx;
+		}
+		if e2 <= dx {
+			err += dx;
+			y += sy;
+		}
+	}
+}
+
+pub(crate) fn text_width(text: &str, scale: i32) -> i32 {
+	text.chars().count() as i32 * 6 * scale
+}
+
+// Text in a 5x7 pixel font, since there's no font to render with. Lowercase is drawn as
+// uppercase.
+pub(crate) fn draw_text(
+	img: &mut RgbImage,
+	x: i32,
+	y: i32,
+	text: &str,
+	scale: i32,
+	colour: Rgb<u8>,
+) {
+	for (i, c) in text.chars().enumerate() {
+		let char_x = x + i as i32 * 6 * scale;
+		for (row, bits) in glyph(c).iter().enumerate() {
+			for col in 0..5 {
+				if bits & (0x10 >> col) != 0 {
+					fill_rect(
+						img,
+						char_x + col * scale,
+						y + row as i32 * scale,
+						scale,
+						scale,
+						colour,
+					);
+				}
+			}
+		}
+	}
+}
+
+fn glyph(c: char) -> [u8; 7] {
+	match c.to_ascii_uppercase() {
+		'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+		'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+		'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+		'D' => [0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E],
+		'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+		'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+		'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+		'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+		'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+		'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+		'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+		'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+		'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+		'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+		'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+		'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+		'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+		'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+		'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+		'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+		'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+		'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+		'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+		'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+		'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+		'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+		'0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+		'1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+		'2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+		'3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+		'4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+		'5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+		'6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+		'7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+		'8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+		'9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+		'-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+		'.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+		':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+		',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+		'\'' => [0x0C, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00],
+		'+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+		'#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+		'(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+		')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+		' ' => [0x00; 7],
+		_ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+	}
+}
@@ -0,0 +1,405 @@
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+use serde::Deserialize;
+use tokio::fs;
+
+use crate::output::OutputFormat;
+use common::errors::{ConfigError, EntryError, ToolError};
+
+pub const CONFIG_FILENAME: &str = "statter.toml";
+
+// Optional settings, read from statter.toml in the source folder.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct StatterConfig {
+	pub discovery: Discovery,
+	pub rosters: RostersConfig,
+	pub players: PlayersConfig,
+	pub output: OutputConfig,
+	pub overlay: OverlayConfig,
+}
+
+impl StatterConfig {
+	pub async fn load(source: &Path) -> Result<Self, ToolError> {
+		let config_path = source.join(CONFIG_FILENAME);
+		if !config_path.is_file() {
+			return Ok(Self::default());
+		}
+
+		let config_string = fs::read_to_string(&config_path).await?;
+		toml::from_str(&config_string).map_err(|e| {
+			ConfigError::InvalidConfig(config_path.display().to_string(), e.to_string()).into()
+		})
+	}
+
+	// Cups of the finished tournaments. Statter's own files are left out, even if the include
+	// patterns match them: this config, the manifest, the player identities, the live cup,
+	// which is only read for the overlay, and everything in the roster folder.
+	pub async fn cup_paths(&self, source: &Path) -> Result<Vec<PathBuf>, ToolError> {
+		let own_files: Vec<PathBuf> = [
+			Some(PathBuf::from(CONFIG_FILENAME)),
+			self.discovery.manifest.clone(),
+			self.players.identities.clone(),
+			self.overlay.cup.clone(),
+		]
+		.into_iter()
+		.flatten()
+		.filter_map(|file| source.join(file).canonicalize().ok())
+		.collect();
+		let rosters_folder = self
+			.rosters
+			.folder
+			.as_ref()
+			.and_then(|folder| source.join(folder).canonicalize().ok());
+
+		let mut cup_paths = self.discovery.cup_paths(source).await?;
+		cup_paths.retain(|path| {
+			let Ok(path) = path.canonicalize() else {
+				return true;
+			};
+			!own_files.contains(&path)
+				&& !rosters_folder.as_ref().is_some_and(|f| path.starts_with(f))
+		});
+		Ok(cup_paths)
+	}
+
+	// Every file a run reads from, so it can be run again when one of them changes. Cup files
+	// that can't be found are left out, since the run fails on them anyway.
+	pub async fn input_files(&self, source: &Path) -> Vec<PathBuf> {
+		let mut files = vec![source.join(CONFIG_FILENAME)];
+		files.extend(self.cup_paths(source).await.unwrap_or_default());
+		if let Some(manifest) = &self.discovery.manifest {
+			files.push(source.join(manifest));
+		}
+		if let Some(cup) = &self.overlay.cup {
+			files.push(source.join(cup));
+		}
+		if let Some(identities) = &self.players.identities {
+			files.push(source.join(identities));
+		}
+		if let Some(folder) = &self.rosters.folder {
+			if let Ok(mut entries) = fs::read_dir(source.join(folder)).await {
+				while let Ok(Some(entry)) = entries.next_entry().await {
+					files.push(entry.path());
+				}
+			}
+		}
+		files
+	}
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct Discovery {
+	// Patterns without a '/' are matched against the file name, others against the path
+	// relative to the source folder.
+	pub include: Vec<String>,
+	pub exclude: Vec<String>,
+	pub recursive: bool,
+	// Lists the cup files explicitly, which skips include/exclude and the folder search.
+	pub manifest: Option<PathBuf>,
+}
+
+impl Default for Discovery {
+	fn default() -> Self {
+		Self {
+			include: vec!["*bigfunnycup*.toml".to_string()],
+			exclude: Vec::new(),
+			recursive: false,
+			manifest: None,
+		}
+	}
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct RostersConfig {
+	// Folder with the team TOML files made by lineupper, relative to the source folder.
+	pub folder: Option<PathBuf>,
+	// Fail if a scorer or assister isn't on the roster of the team they're credited for.
+	pub validate: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct PlayersConfig {
+	// File mapping player name aliases to one player, relative to the source folder.
+	pub identities: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct OutputConfig {
+	// Format of the results, team and league files: "toml", "json" or "yaml".
+	pub format: OutputFormat,
+	// Also write a static HTML site to the "site" folder in the destination.
+	pub html: bool,
+	// Also write an SVG bracket next to each tournament's results.
+	pub svg: bool,
+	// Also write PNG charts of the ranks and ranking points over each season.
+	pub charts: bool,
+	// Also write every statistic to "stats.db", an SQLite database, for ad-hoc queries.
+	pub sqlite: bool,
+	// Also write CSV tables for spreadsheets to the "csv" folder in the destination.
+	pub csv: bool,
+	// Also write a Markdown report next to each tournament's results.
+	pub markdown: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct OverlayConfig {
+	// Cup file of the tournament being played, relative to the source folder. It's left out
+	// of the statistics, since it isn't finished, and is only used for the stream overlay.
+	pub cup: Option<PathBuf>,
+	// Folder the overlay's text and PNG files are written to, relative to the destination
+	// folder.
+	pub folder: PathBuf,
+}
+
+impl Default for OverlayConfig {
+	fn default() -> Self {
+		Self {
+			cup: None,
+			folder: PathBuf::from("overlay"),
+		}
+	}
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+	cups: Vec<PathBuf>,
+}
+
+impl Discovery {
+	// Cup paths in the order they should be merged in: manifest order if there is one,
+	// otherwise sorted by path, which only matters for tournaments on the same date.
+	async fn cup_paths(&self, source: &Path) -> Result<Vec<PathBuf>, ToolError> {
+		if let Some(manifest) = &self.manifest {
+			return Self::manifest_cup_paths(&source.join(manifest)).await;
+		}
+
+		let include = Self::patterns(&self.include)?;
+		let exclude = Self::patterns(&self.exclude)?;
+
+		let mut cup_file_paths = Vec::new();
+		let mut folders = vec![source.to_path_buf()];
+		while let Some(folder) = folders.pop() {
+			let mut entries = fs::read_dir(&folder).await?;
+
+			loop {
+				match entries.next_entry().await {
+					Ok(None) => break,
+					Ok(Some(entry)) => {
+						let path = entry.path();
+						if path.is_dir() {
+							// Symlinked folders are skipped, since they could loop back.
+							let is_symlink = entry.file_type().await.is_ok_and(|t| t.is_symlink());
+							if self.recursive && !is_symlink {
+								folders.push(path);
+							}
+							continue;
+						}
+
+						let relative_path = path.strip_prefix(source).unwrap_or(&path);
+						if path.is_file()
+							&& Self::matches_any(&include, relative_path)
+							&& !Self::matches_any(&exclude, relative_path)
+						{
+							cup_file_paths.push(path);
+						}
+					}
+					Err(_) => {
+						return Err(EntryError::SourcePathReadError(
+							folder.to_string_lossy().to_string(),
+						)
+						.into())
+					}
+				}
+			}
+		}
+
+		// Directory order isn't guaranteed, and results are merged in this order.
+		cup_file_paths.sort_unstable();
+		Ok(cup_file_paths)
+	}
+
+	async fn manifest_cup_paths(manifest_path: &Path) -> Result<Vec<PathBuf>, ToolError> {
+		let manifest_string = fs::read_to_string(manifest_path).await.map_err(|e| {
+			ConfigError::InvalidManifest(manifest_path.display().to_string(), e.to_string())
+		})?;
+		let manifest: Manifest = toml::from_str(&manifest_string).map_err(|e| {
+			ConfigError::InvalidManifest(manifest_path.display().to_string(), e.to_string())
+		})?;
+
+		// Cups are listed relative to the manifest.
+		let manifest_folder = manifest_path.parent().unwrap_or(Path::new(""));
+		let mut cup_file_paths = Vec::with_capacity(manifest.cups.len());
+		for cup in manifest.cups {
+			let cup_path = manifest_folder.join(&cup);
+			if !cup_path.is_file() {
+				return Err(ConfigError::MissingManifestCup(
+					manifest_path.display().to_string(),
+					cup.display().to_string(),
+				)
+				.into());
+			}
+			cup_file_paths.push(cup_path);
+		}
+
+		Ok(cup_file_paths)
+	}
+
+	fn patterns(patterns: &[String]) -> Result<Vec<Pattern>, ToolError> {
+		patterns
+			.iter()
+			.map(|p| {
+				Pattern::new(p)
+					.map_err(|e| ConfigError::InvalidPattern(p.clone(), e.to_string()).into())
+			})
+			.collect()
+	}
+
+	fn matches_any(patterns: &[Pattern], relative_path: &Path) -> bool {
+		let options = MatchOptions {
+			case_sensitive: true,
+			require_literal_separator: true,
+			require_literal_leading_dot: false,
+		};
+		let file_name = relative_path.file_name().map(Path::new);
+		// Normalize separators so patterns written with '/' also work on Windows.
+		let relative_path = relative_path.to_string_lossy().replace('\\', "/");
+
+		patterns.iter().any(|p| {
+			if p.as_str().contains('/') {
+				p.matches_with(&relative_path, options)
+			} else {
+				file_name.is_some_and(|f| p.matches_path_with(f, options))
+			}
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_support::{block_on, TempDir};
+
+	fn cup_names(config_toml: &str, source: &TempDir) -> Result<Vec<String>, ToolError> {
+		let config: StatterConfig = toml::from_str(config_toml).unwrap();
+		let cup_paths = block_on(config.cup_paths(source.path()))?;
+		Ok(cup_paths
+			.iter()
+			.map(|path| {
+				let relative_path = path.strip_prefix(source.path()).unwrap_or(path);
+				relative_path.to_string_lossy().replace('\\', "/")
+			})
+			.collect())
+	}
+
+	#[test]
+	fn include_and_exclude() {
+		let source = TempDir::new();
+		for file in [
+			"bigfunnycup-2.toml",
+			"bigfunnycup-1.toml",
+			"old-bigfunnycup.toml",
+			"notes.toml",
+		] {
+			source.write(file, "");
+		}
+
+		assert_eq!(
+			cup_names("", &source).unwrap(),
+			[
+				"bigfunnycup-1.toml",
+				"bigfunnycup-2.toml",
+				"old-bigfunnycup.toml"
+			]
+		);
+		assert_eq!(
+			cup_names("[discovery]\nexclude = [\"old-*\"]", &source).unwrap(),
+			["bigfunnycup-1.toml", "bigfunnycup-2.toml"]
+		);
+	}
+
+	#[test]
+	fn broad_include_skips_own_files() {
+		let source = TempDir::new();
+		for file in [
+			"statter.toml",
+			"cup.toml",
+			"players.toml",
+			"live.toml",
+			"rosters/cursed.toml",
+		] {
+			source.write(file, "");
+		}
+		let config = "[discovery]\ninclude = [\"*.toml\"]\nrecursive = true\n\
+			[players]\nidentities = \"players.toml\"\n\
+			[overlay]\ncup = \"live.toml\"\n\
+			[rosters]\nfolder = \"rosters\"";
+
+		assert_eq!(cup_names(config, &source).unwrap(), ["cup.toml"]);
+	}
+
+	#[test]
+	fn recursive_search() {
+		let source = TempDir::new();
+		source.write("bigfunnycup-1.toml", "");
+		source.write("season-2/bigfunnycup-2.toml", "");
+		source.write("season-2/extra/bigfunnycup-3.toml", "");
+
+		assert_eq!(cup_names("", &source).unwrap(), ["bigfunnycup-1.toml"]);
+		assert_eq!(
+			cup_names("[discovery]\nrecursive = true", &source).unwrap(),
+			[
+				"bigfunnycup-1.toml",
+				"season-2/bigfunnycup-2.toml",
+				"season-2/extra/bigfunnycup-3.toml"
+			]
+		);
+		assert_eq!(
+			cup_names(
+				"[discovery]\nrecursive = true\ninclude = [\"season-2/*.toml\"]",
+				&source
+			)
+			.unwrap(),
+			["season-2/bigfunnycup-2.toml"]
+		);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn symlinked_folders_are_skipped() {
+		let source = TempDir::new();
+		source.write("season-1/bigfunnycup-1.toml", "");
+		std::os::unix::fs::symlink(source.path(), source.path().join("season-1/loop")).unwrap();
+
+		assert_eq!(
+			cup_names("[discovery]\nrecursive = true", &source).unwrap(),
+			["season-1/bigfunnycup-1.toml"]
+		);
+	}
+
+	#[test]
+	fn manifest_order_and_folder() {
+		let source = TempDir::new();
+		source.write("cups/b.toml", "");
+		source.write("cups/a.toml", "");
+		source.write("cups/manifest.toml", "cups = [\"b.toml\", \"a.toml\"]");
+		let config = "[discovery]\nmanifest = \"cups/manifest.toml\"";
+
+		assert_eq!(
+			cup_names(config, &source).unwrap(),
+			["cups/b.toml", "cups/a.toml"]
+		);
+
+		source.write("cups/manifest.toml", "cups = [\"b.toml\", \"c.toml\"]");
+		assert!(matches!(
+			cup_names(config, &source),
+			Err(ToolError::ConfigError(ConfigError::MissingManifestCup(..)))
+		));
+	}
+}
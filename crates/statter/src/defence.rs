@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::players::PlayerIdentities;
+use crate::rosters::TeamRosters;
+use crate::team::{sort_player_counts, Team};
+use crate::tournament::TournamentResult;
+use common::{PlayerName, TeamName};
+
+// Clean sheets of each team's goalkeepers. A fixture credits the keeper entered for it, or
+// else the active goalkeepers on the team's roster when rosters are used. Fixtures with
+// neither don't count.
+pub fn keeper_clean_sheets(
+	tourny_results: &[TournamentResult],
+	rosters: Option<&TeamRosters>,
+	identities: &PlayerIdentities,
+) -> HashMap<TeamName, Vec<(PlayerName, u32)>> {
+	let mut teams_keepers: HashMap<TeamName, Vec<(PlayerName, u32)>> = HashMap::new();
+	for tr in tourny_results {
+		for fixture in tr.rounds.iter().flat_map(|r| &r.fixtures) {
+			for (team, keeper, goals_against) in [
+				(fixture.team1, &fixture.keeper1, fixture.score2),
+				(fixture.team2, &fixture.keeper2, fixture.score1),
+			] {
+				let fixture_keepers = match (keeper, rosters) {
+					(Some(keeper), _) => vec![keeper.clone()],
+					(None, Some(rosters)) => rosters.keepers(team, identities),
+					(None, None) => continue,
+				};
+				let clean_sheet = (goals_against == 0) as u32;
+				for keeper in fixture_keepers {
+					let keepers = teams_keepers.entry(team).or_default();
+					match keepers.iter_mut().find(|(k, _)| *k == keeper) {
+						Some((_, clean_sheets)) => *clean_sheets += clean_sheet,
+						None => keepers.push((keeper, clean_sheet)),
+					}
+				}
+			}
+		}
+	}
+
+	for keepers in teams_keepers.values_mut() {
+		sort_player_counts(keepers);
+	}
+	teams_keepers
+}
+
+#[derive(Serialize)]
+pub struct TeamDefence {
+	pub team: TeamName,
+	pub matches_played: u32,
+	pub clean_sheets: u32,
+	pub failed_to_score: u32,
+	pub goals_against: u32,
+	pub goals_against_per_match: f64,
+}
+
+#[derive(Serialize)]
+pub struct KeeperCleanSheets {
+	pub player: PlayerName,
+	pub team: TeamName,
+	pub clean_sheets: u32,
+}
+
+// Teams ranked by clean sheets, then fewest goals conceded per match.
+#[derive(Serialize)]
+pub struct DefenceLeaderboards {
+	pub teams: Vec<TeamDefence>,
+	pub goalkeepers: Vec<KeeperCleanSheets>,
+}
+
+impl DefenceLeaderboards {
+	pub fn from<'a>(teams: impl Iterator<Item = &'a Team>) -> Self {
+		let mut team_entries = Vec::new();
+		let mut goalkeepers = Vec::new();
+		for team in teams {
+			team_entries.push(TeamDefence {
+				team: team.name,
+				matches_played: team.matches_played(),
+				clean_sheets: team.clean_sheets,
+				failed_to_score: team.failed_to_score,
+				goals_against: team.goals_against,
+				goals_against_per_match: team.goals_against_per_match,
+			});
+			for (player, clean_sheets) in team.keeper_clean_sheets.iter().flatten() {
+				goalkeepers.push(KeeperCleanSheets {
+					player: player.clone(),
+					team: team.name,
+					clean_sheets: *clean_sheets,
+				});
+			}
+		}
+
+		team_entries.sort_unstable_by(|a, b| {
+			b.clean_sheets
+				.cmp(&a.clean_sheets)
+				.then(
+					a.goals_against_per_match
+						.total_cmp(&b.goals_against_per_match),
+				)
+				.then(a.team.cmp(&b.team))
+		});
+		goalkeepers.sort_unstable_by(|a, b| {
+			b.clean_sheets
+				.cmp(&a.clean_sheets)
+				.then(
+					a.player
+						.to_ascii_lowercase()
+						.cmp(&b.player.to_ascii_lowercase()),
+				)
+				.then(a.team.cmp(&b.team))
+		});
+
+		Self {
+			teams: team_entries,
+			goalkeepers,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_support::{self, block_on, cup, fixture, identities, TempDir};
+
+	#[test]
+	fn roster_keepers_cover_fixtures_without_one() {
+		let folder = TempDir::new();
+		folder.write(
+			"cursed.toml",
+			r#"
+reserve = []
+
+[[active]]
+id = 1
+name = "Gigi"
+position = "GK"
+
+[[active]]
+id = 9
+name = "Toni"
+position = "CF"
+"#,
+		);
+		let rosters = block_on(TeamRosters::load(
+			folder.path(),
+			[TeamName::Cursed, TeamName::Moai].into_iter(),
+		))
+		.unwrap();
+		assert_eq!(rosters.missing().len(), 1);
+		let identities = identities(
+			r#"
+[[players]]
+id = "buffon"
+name = "Buffon"
+aliases = ["Gigi"]
+"#,
+		)
+		.unwrap();
+
+		let without_keeper = fixture("brackets.winners", "Cursed", "Moai", 2, 0);
+		let with_keeper = without_keeper.replacen("\n\n", "\nkeeper1 = \"Toni\"\n\n", 1);
+		let results = [
+			test_support::run(&cup(2, false, &[without_keeper])),
+			test_support::run(&cup(2, false, &[with_keeper])),
+		];
+
+		let keepers = keeper_clean_sheets(&results, Some(&rosters), &identities);
+		assert_eq!(
+			keepers[&TeamName::Cursed],
+			[("Buffon".to_string(), 1), ("Toni".to_string(), 1)]
+		);
+		// Moai has no roster, so only entered keepers would count.
+		assert!(!keepers.contains_key(&TeamName::Moai));
+
+		let keepers = keeper_clean_sheets(&results, None, &identities);
+		assert_eq!(keepers[&TeamName::Cursed], [("Toni".to_string(), 1)]);
+	}
+}
@@ -1,131 +1,360 @@
-use std::collections::HashMap;
-use std::ffi::OsStr;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use iced::task::{sipper, Straw};
-use tokio::fs;
+use iced::task::{sipper, Sipper, Straw};
+use tokio::{fs, task, task::JoinSet};
 
+use crate::cache::{cup_key, CachedCup, CupCache};
+use crate::charts::write_season_charts;
+use crate::config::StatterConfig;
+use crate::defence::{keeper_clean_sheets, DefenceLeaderboards};
+use crate::honours::{TitlesTable, TrophyCabinet};
+use crate::overlay::{load_live_tournament, write_overlay};
+use crate::partnerships::Partnerships;
+use crate::players::{near_duplicates, PlayerIdentities};
 use crate::rankings::Seasons;
-use crate::team::Team;
+use crate::records::Records;
+use crate::report::tournament_report;
+use crate::rosters::TeamRosters;
+use crate::site::Site;
+use crate::spreadsheet::write_spreadsheets;
+use crate::sqlite::write_database;
+use crate::streaks::{LeagueStreaks, Streaks};
+use crate::svg::bracket_svg;
+use crate::team::{sort_partnerships, sort_player_counts, SeasonStats, Team};
 use crate::tournament::{Participation, Tournament, TournamentResult};
 use common::{
 	errors::{EntryError, ToolError},
 	Progress, TeamName,
 };
 
+// Everything statter works out from a source folder, before any of it is written.
+pub struct Statistics {
+	pub config: StatterConfig,
+	// In manifest order, or sorted by date without a manifest. With sorted scorers, assisters
+	// and partnerships.
+	pub tourny_results: Vec<TournamentResult>,
+	pub teams: HashMap<TeamName, Team>,
+	pub seasons: Seasons,
+	pub records: Records,
+	pub streaks: LeagueStreaks,
+	pub cache: CupCache,
+	// The unfinished tournament named in the overlay config, if any. It's usually being
+	// edited during a stream, so failing to read it only affects the overlay.
+	pub live_tournament: Option<Result<Tournament, ToolError>>,
+}
+
+impl Statistics {
+	// Teams sorted by name.
+	pub fn sorted_teams(&self) -> Vec<&Team> {
+		let mut teams: Vec<&Team> = self.teams.values().collect();
+		teams.sort_unstable_by_key(|t| t.name);
+		teams
+	}
+}
+
 pub fn run_tournaments(
 	source: PathBuf,
 	destination: PathBuf,
 ) -> impl Straw<(), Progress, ToolError> {
 	sipper(async move |mut progress| {
-		let _ = progress.send(Progress { percent: 0.0 });
-		let cup_paths = get_cup_paths(&source).await?;
-		if cup_paths.is_empty() {
-			return Err(EntryError::MissingTournamentFiles.into());
+		// Reuse results from the cache for cups that haven't changed since the last run.
+		let old_cache = CupCache::load(&destination).await;
+		// Shared with the blocking task that writes the database.
+		let statistics = Arc::new(
+			compute_statistics(source, Arc::new(old_cache))
+				.run(&progress)
+				.await?,
+		);
+		let config = &statistics.config;
+		let all_tournament_results = &statistics.tourny_results;
+		let seasons = &statistics.seasons;
+		let records = &statistics.records;
+
+		// Generate the stats folder.
+		let output_format = config.output.format;
+		if !destination.is_dir() {
+			fs::create_dir(&destination).await?;
 		}
+		statistics.cache.save(&destination).await?;
 
-		// Run all tournaments.
-		let mut teams_total_stats: HashMap<TeamName, Team> = HashMap::new();
-		let mut all_tournament_results: Vec<TournamentResult> = Vec::new();
+		let mut percent_done = 41.0;
+		let fraction_per_cup = 30.0 / all_tournament_results.len() as f32;
 
-		let mut percent_done = 1.0; // Getting the paths count as 1%, I guess.
-		let fraction_per_cup = 30.0 / cup_paths.len() as f32; // And generating stats is 30%, I guess.
-		for cup in cup_paths {
+		// Generate tournament results.
+		for tournament_results in all_tournament_results {
 			let _ = progress
 				.send(Progress {
 					percent: percent_done,
 				})
 				.await;
-			percent_done += fraction_per_cup;
+			percent_done += fraction_per_cup; // Another 30% here, up to 71%.
+
+			let tournament_results_string = output_format.to_string(&tournament_results)?;
+			let tournament_results_path = destination.join(format!(
+				"{}-results.{}",
+				tournament_results.slug(),
+				output_format.extension()
+			));
 
-			let tournament: Tournament = toml::from_str(&fs::read_to_string(cup).await?)?;
-			let mut teams_results = tournament.run()?;
-			let mut tournament_goal_scorers = Vec::new();
-			let mut tournament_assisters = Vec::new();
+			fs::write(tournament_results_path, tournament_results_string).await?;
 
-			// Add tournament team stats to teams_total_stats stats.
-			for tp in &mut teams_results {
-				// Create participation for this tournament.
-				let participation = Participation::new(
-					tournament.tournament_name.clone(),
-					tp.placement.ok_or(EntryError::MissingTeamPlacement(
-						tournament.tournament_name.clone(),
-						tp.team.name,
-					))?,
-					tournament.date,
-				);
-				// Add the tournament participation to the team.
-				if let Some(p) = &mut tp.team.participations {
-					p.push(participation);
-				} else {
-					tp.team.participations = Some(vec![participation]);
-				}
+			if config.output.svg {
+				let bracket_path =
+					destination.join(format!("{}-bracket.svg", tournament_results.slug()));
+				fs::write(bracket_path, bracket_svg(tournament_results)).await?;
+			}
+		}
 
-				for (player, other_goals) in tp.team.scorers.iter() {
-					match tournament_goal_scorers
-						.iter_mut()
-						.find(|(p, _, t)| p == player && *t == tp.team.name)
-					{
-						Some((_, goals, _)) => *goals += other_goals,
-						None => tournament_goal_scorers.push((
-							player.to_owned(),
-							*other_goals,
-							tp.team.name,
-						)),
-					}
-				}
+		// Write records across all tournaments.
+		let records_string = output_format.to_string(&records)?;
+		let records_path = destination.join(format!("records.{}", output_format.extension()));
+		fs::write(records_path, records_string).await?;
 
-				for (player, other_assists) in tp.team.assisters.iter() {
-					match tournament_assisters
-						.iter_mut()
-						.find(|(p, _, t)| p == player && *t == tp.team.name)
-					{
-						Some((_, assists, _)) => *assists += other_assists,
-						None => tournament_assisters.push((
-							player.to_owned(),
-							*other_assists,
-							tp.team.name,
-						)),
-					}
-				}
+		// Write streaks, which also go in the team files.
+		let streaks_string = output_format.to_string(&statistics.streaks)?;
+		let streaks_path = destination.join(format!("streaks.{}", output_format.extension()));
+		fs::write(streaks_path, streaks_string).await?;
 
-				teams_total_stats
-					.entry(tp.team.name)
-					.or_insert(Team::from(tp.team.name))
-					.add(&mut tp.team)?;
+		// Write SeasonRankings.
+		let rankings_string = output_format.to_string(&seasons)?;
+		let rankings_path = destination.join(format!("rankings.{}", output_format.extension()));
+		fs::write(rankings_path, rankings_string).await?;
+		if config.output.charts {
+			write_season_charts(all_tournament_results, seasons, &destination).await?;
+		}
+		if config.output.markdown {
+			for (idx, tournament_results) in all_tournament_results.iter().enumerate() {
+				let report_path =
+					destination.join(format!("{}-report.md", tournament_results.slug()));
+				let report = tournament_report(all_tournament_results, idx, seasons);
+				fs::write(report_path, report).await?;
 			}
+		}
 
-			// Add TournamentResult to seasons.
-			all_tournament_results.push(TournamentResult::from(
-				teams_results,
-				tournament,
-				tournament_goal_scorers,
-				tournament_assisters,
-			));
+		percent_done += 2.0; // Up to 73%.
+
+		// Write team stats.
+		let teams = statistics.sorted_teams();
+		let fraction_per_team = 27.0 / teams.len() as f32;
+		for team in teams {
+			let _ = progress
+				.send(Progress {
+					percent: percent_done,
+				})
+				.await;
+			percent_done += fraction_per_team;
+
+			let team_string = output_format.to_string(&team)?;
+			let team_path = destination.join(team.filename(output_format));
+			fs::write(team_path, team_string).await?;
 		}
-		let _ = progress
-			.send(Progress {
-				percent: percent_done,
+
+		// Write the titles table.
+		let titles_string =
+			output_format.to_string(&TitlesTable::from(statistics.teams.values()))?;
+		let titles_path = destination.join(format!("titles.{}", output_format.extension()));
+		fs::write(titles_path, titles_string).await?;
+
+		// Write defence leaderboards.
+		let defence_string =
+			output_format.to_string(&DefenceLeaderboards::from(statistics.teams.values()))?;
+		let defence_path = destination.join(format!("defence.{}", output_format.extension()));
+		fs::write(defence_path, defence_string).await?;
+
+		// Write top scorer–assister partnerships.
+		let partnerships_string =
+			output_format.to_string(&Partnerships::from(statistics.teams.values()))?;
+		let partnerships_path =
+			destination.join(format!("partnerships.{}", output_format.extension()));
+		fs::write(partnerships_path, partnerships_string).await?;
+
+		if config.output.sqlite {
+			let statistics = Arc::clone(&statistics);
+			let database_path = destination.join("stats.db");
+			task::spawn_blocking(move || {
+				write_database(
+					&statistics.tourny_results,
+					statistics.teams.values(),
+					&statistics.seasons,
+					&database_path,
+				)
 			})
-			.await; // Need to update final cup processed.
+			.await
+			.map_err(|e| EntryError::WriteTaskFailure(String::from("stats.db"), e.to_string()))??;
+		}
 
-		// Generate the stats folder.
-		if !destination.is_dir() {
-			fs::create_dir(&destination).await?;
+		if config.output.csv {
+			write_spreadsheets(
+				all_tournament_results,
+				statistics.teams.values(),
+				seasons,
+				&destination.join("csv"),
+			)
+			.await?;
 		}
-		percent_done += 1.0; // Up to 32%.
 
-		// Generate tournament results.
-		all_tournament_results.sort_unstable_by_key(|k| k.date);
+		if config.output.html {
+			Site::from(
+				all_tournament_results,
+				statistics.teams.values(),
+				seasons,
+				records,
+			)
+			.write(&destination.join("site"))
+			.await?;
+		}
 
-		for tournament_results in &mut all_tournament_results {
+		// Last, so a broken live cup can't hold back the finished statistics.
+		let overlay_result = match &statistics.live_tournament {
+			Some(Ok(live_tournament)) => {
+				write_overlay(live_tournament, &destination.join(&config.overlay.folder)).await
+			}
+			Some(Err(e)) => Err(e.clone()),
+			None => Ok(()),
+		};
+		if let Err(e) = overlay_result {
+			eprintln!("ATTENTION: Skipped the overlay: {e}");
+		}
+
+		let _ = progress.send(Progress { percent: 100.0 }).await;
+		Ok(())
+	})
+}
+
+// Runs every cup in the source folder and aggregates the results, without writing anything.
+// Cups with a result in old_cache are not run again.
+pub fn compute_statistics(
+	source: PathBuf,
+	old_cache: Arc<CupCache>,
+) -> impl Straw<Statistics, Progress, ToolError> {
+	sipper(async move |mut progress| {
+		let _ = progress.send(Progress { percent: 0.0 }).await;
+		let config = StatterConfig::load(&source).await?;
+		let cup_paths = config.cup_paths(&source).await?;
+		// The tournament being played is only read for the overlay.
+		let live_cup = config.overlay.cup.as_ref().map(|cup| source.join(cup));
+		if cup_paths.is_empty() {
+			return Err(EntryError::MissingTournamentFiles.into());
+		}
+
+		// Resolve player aliases, if there's an identity file.
+		let rosters_folder = config.rosters.folder.as_ref().map(|f| source.join(f));
+		let identities = Arc::new(match &config.players.identities {
+			Some(path) => {
+				PlayerIdentities::load(&source.join(path), rosters_folder.as_deref()).await?
+			}
+			None => PlayerIdentities::default(),
+		});
+
+		let live_tournament = match &live_cup {
+			Some(live_cup) => Some(load_live_tournament(live_cup, &identities).await),
+			None => None,
+		};
+
+		let mut new_cache = CupCache::new();
+
+		// Run all tournaments. Every cup is independent until aggregation, so they're read and
+		// run concurrently, then merged in a fixed order so the output doesn't depend on which
+		// cup finished first.
+		let cup_count = cup_paths.len();
+		let mut cup_runs = JoinSet::new();
+		for (idx, cup) in cup_paths.into_iter().enumerate() {
+			let old_cache = Arc::clone(&old_cache);
+			let identities = Arc::clone(&identities);
+			cup_runs.spawn(async move {
+				let cup_string = fs::read_to_string(&cup).await?;
+				let hash = cup_key(&cup_string, &identities);
+				let result = match old_cache.get(&hash) {
+					Some(cached) => cached.result.clone(),
+					None => task::spawn_blocking(move || {
+						run_cup(toml::from_str(&cup_string)?, &identities)
+					})
+					.await
+					.map_err(|e| EntryError::CupTaskFailure(e.to_string()))??,
+				};
+				Ok::<_, ToolError>((idx, CachedCup { hash, result }))
+			});
+		}
+
+		let mut percent_done = 1.0; // Getting the paths count as 1%, I guess.
+		let fraction_per_cup = 30.0 / cup_count as f32; // And generating stats is 30%, I guess.
+		let mut cups_done: Vec<Option<CachedCup>> = vec![None; cup_count];
+		while let Some(joined) = cup_runs.join_next().await {
+			let (idx, cached_cup) =
+				joined.map_err(|e| EntryError::CupTaskFailure(e.to_string()))??;
+			cups_done[idx] = Some(cached_cup);
+
+			percent_done += fraction_per_cup;
 			let _ = progress
 				.send(Progress {
 					percent: percent_done,
 				})
 				.await;
-			percent_done += fraction_per_cup; // Another 30% here, up to 62%.
+		}
+
+		// A manifest lists the cups in order. Otherwise merge in date order, falling back on
+		// path order for cups on the same date.
+		let mut cups_done: Vec<CachedCup> = cups_done.into_iter().flatten().collect();
+		if config.discovery.manifest.is_none() {
+			cups_done.sort_by_key(|c| c.result.date);
+		}
+
+		let mut teams_total_stats: HashMap<TeamName, Team> = HashMap::new();
+		let mut teams_season_stats: HashMap<TeamName, BTreeMap<u8, Team>> = HashMap::new();
+		let mut all_tournament_results: Vec<TournamentResult> = Vec::new();
+		for cached_cup in cups_done {
+			// Add tournament team stats to teams_total_stats stats.
+			for tp in &cached_cup.result.team_placements {
+				teams_total_stats
+					.entry(tp.team.name)
+					.or_insert(Team::from(tp.team.name))
+					.add(&mut tp.team.clone())?;
+				// Same, but only for the tournament's season.
+				teams_season_stats
+					.entry(tp.team.name)
+					.or_default()
+					.entry(cached_cup.result.season_num)
+					.or_insert(Team::from(tp.team.name))
+					.add(&mut tp.team.clone())?;
+			}
 
+			all_tournament_results.push(cached_cup.result.clone());
+			new_cache.push(cached_cup);
+		}
+
+		// Load rosters once, for validating players and crediting goalkeepers.
+		let rosters = match &rosters_folder {
+			Some(folder) => {
+				Some(TeamRosters::load(folder, teams_total_stats.keys().copied()).await?)
+			}
+			None => None,
+		};
+		if let Some(rosters) = rosters.as_ref().filter(|_| config.rosters.validate) {
+			for warning in rosters.missing() {
+				eprintln!("ATTENTION: {warning}");
+			}
+			let violations = rosters.violations(&all_tournament_results, &identities);
+			if !violations.is_empty() {
+				return Err(EntryError::RosterViolations(violations.join("\n")).into());
+			}
+		}
+
+		// Warn about names that are probably the same player, but weren't resolved to one.
+		let mut teams: Vec<&Team> = teams_total_stats.values().collect();
+		teams.sort_unstable_by_key(|t| t.name);
+		for team in teams {
+			for (a, b) in near_duplicates(team) {
+				eprintln!(
+					"ATTENTION: '{a}' and '{b}' of {} look like the same player. Add an alias to merge them.",
+					team.name
+				);
+			}
+		}
+
+		// Tidy up the tournament results. NOTE: TournamentResults are already in order.
+		for tournament_results in &mut all_tournament_results {
 			// Don't clutter tournament results with historic team data.
 			tournament_results
 				.team_placements
@@ -155,96 +384,143 @@ pub fn run_tournaments(
 					)
 				},
 			);
-			let tournament_results_toml = toml::to_string(&tournament_results)?;
-			let tournament_results_path = destination.join(format!(
-				"{}-results.toml",
-				tournament_results
-					.tournament_name
-					.to_lowercase()
-					.replace(' ', "-")
-					.replace(|c: char| !c.is_ascii() || c == ':', "")
-			));
-
-			fs::write(tournament_results_path, tournament_results_toml).await?;
+			tournament_results.partnerships.sort_unstable_by(
+				|(a_scorer, a_assister, a_goals, _a_team),
+				 (b_scorer, b_assister, b_goals, _b_team)| {
+					b_goals
+						.cmp(a_goals)
+						.then(
+							a_scorer
+								.to_ascii_lowercase()
+								.cmp(&b_scorer.to_ascii_lowercase()),
+						)
+						.then(
+							a_assister
+								.to_ascii_lowercase()
+								.cmp(&b_assister.to_ascii_lowercase()),
+						)
+				},
+			);
 		}
 
-		// Generate SeasonRankings. NOTE: TournamentResults are already sorted by date.
-		let seasons = Seasons::from(all_tournament_results);
-		let rankings_toml = toml::to_string(&seasons)?;
-		let rankings_path = destination.join("rankings.toml");
-		fs::write(rankings_path, rankings_toml).await?;
+		// Generate records across all tournaments.
+		let records = Records::from(&all_tournament_results);
 
-		percent_done += 2.0; // Up to 64%.
+		// Generate streaks, which also go in the team files.
+		let teams_streaks = Streaks::all_from(&all_tournament_results);
+		let streaks = LeagueStreaks::from(&teams_streaks);
 
-		// Generate team stats.
-		let teams = teams_total_stats.values_mut();
-		let fraction_per_team = 36.0 / teams.len() as f32;
-		for team in teams {
-			let _ = progress
-				.send(Progress {
-					percent: percent_done,
-				})
-				.await;
-			percent_done += fraction_per_team;
+		// Credit goalkeepers with clean sheets in the fixtures they kept goal in, falling back
+		// to the roster's goalkeepers where no keeper was entered.
+		let mut teams_keepers =
+			keeper_clean_sheets(&all_tournament_results, rosters.as_ref(), &identities);
+
+		// Generate SeasonRankings. NOTE: TournamentResults are already in order.
+		let seasons = Seasons::from(&all_tournament_results);
 
+		// Generate team stats.
+		for team in teams_total_stats.values_mut() {
 			// sort internals of team files first.
-			team.participations
+			let participations = team
+				.participations
 				.as_mut()
-				.ok_or(EntryError::MissingTeamParticipation(team.name))?
-				.sort_unstable_by_key(|p| p.date);
-			team.scorers
-				.sort_unstable_by(|(a_name, a_goals), (b_name, b_goals)| {
-					b_goals.cmp(a_goals).then(
-						a_name
-							.to_ascii_lowercase()
-							.cmp(&b_name.to_ascii_lowercase()),
-					)
-				});
-			team.assisters
-				.sort_unstable_by(|(a_name, a_assists), (b_name, b_assists)| {
-					b_assists.cmp(a_assists).then(
-						a_name
-							.to_ascii_lowercase()
-							.cmp(&b_name.to_ascii_lowercase()),
-					)
-				});
+				.ok_or(EntryError::MissingTeamParticipation(team.name))?;
+			participations.sort_unstable_by_key(|p| p.date);
+			team.trophy_cabinet = Some(TrophyCabinet::from(participations));
+			sort_player_counts(&mut team.scorers);
+			sort_player_counts(&mut team.assisters);
+			sort_partnerships(&mut team.partnerships);
 			team.matchups
 				.as_mut()
 				.ok_or(EntryError::MissingTeamMatchups(team.name))?
 				.sort_unstable_by_key(|mh| mh.opponent_name);
-			let team_toml = toml::to_string(&team)?;
-			let team_path = destination.join(team.filename());
-			fs::write(team_path, team_toml).await?;
+			team.streaks = teams_streaks.get(&team.name).cloned();
+			team.keeper_clean_sheets = teams_keepers.remove(&team.name);
+			team.seasons = teams_season_stats.remove(&team.name).map(|season_teams| {
+				season_teams
+					.into_iter()
+					.map(|(season_num, season_team)| {
+						SeasonStats::from(season_num, season_team, &seasons)
+					})
+					.collect()
+			});
 		}
-		let _ = progress.send(Progress { percent: 100.0 }).await;
-		Ok(())
+
+		let _ = progress.send(Progress { percent: 40.0 }).await;
+
+		Ok(Statistics {
+			config,
+			tourny_results: all_tournament_results,
+			teams: teams_total_stats,
+			seasons,
+			records,
+			streaks,
+			cache: new_cache,
+			live_tournament,
+		})
 	})
 }
 
-async fn get_cup_paths(source: &PathBuf) -> Result<Vec<PathBuf>, ToolError> {
-	let mut cup_file_paths = Vec::new();
-	let mut entries = fs::read_dir(source).await?;
-
-	let extension = Some(OsStr::new("toml"));
-
-	loop {
-		match entries.next_entry().await {
-			Ok(None) => break,
-			Ok(Some(entry)) => {
-				if entry.path().is_file()
-					&& entry.file_name().to_string_lossy().contains("bigfunnycup")
-					&& entry.path().extension() == extension
-				{
-					cup_file_paths.push(entry.path());
+// Runs a single tournament and gathers everything needed from it, so the result can be
+// cached and aggregated with the other tournaments later.
+pub(crate) fn run_cup(
+	mut tournament: Tournament,
+	identities: &PlayerIdentities,
+) -> Result<TournamentResult, ToolError> {
+	tournament.resolve_players(identities);
+	tournament.link_goal_events()?;
+	let (mut teams_results, group_tables) = tournament.run()?;
+	let mut tournament_goal_scorers = Vec::new();
+	let mut tournament_assisters = Vec::new();
+
+	for tp in &mut teams_results {
+		// Create participation for this tournament.
+		let participation = Participation::new(
+			tournament.tournament_name.clone(),
+			tournament.season_num,
+			tp.placement.ok_or(EntryError::MissingTeamPlacement(
+				tournament.tournament_name.clone(),
+				tp.team.name,
+			))?,
+			tournament.date,
+		);
+		// Add the tournament participation to the team.
+		if let Some(p) = &mut tp.team.participations {
+			p.push(participation);
+		} else {
+			tp.team.participations = Some(vec![participation]);
+		}
+
+		for (player, other_goals) in tp.team.scorers.iter() {
+			match tournament_goal_scorers
+				.iter_mut()
+				.find(|(p, _, t)| p == player && *t == tp.team.name)
+			{
+				Some((_, goals, _)) => *goals += other_goals,
+				None => {
+					tournament_goal_scorers.push((player.to_owned(), *other_goals, tp.team.name))
 				}
 			}
-			Err(_) => {
-				return Err(
-					EntryError::SourcePathReadError(source.to_string_lossy().to_string()).into(),
-				)
+		}
+
+		for (player, other_assists) in tp.team.assisters.iter() {
+			match tournament_assisters
+				.iter_mut()
+				.find(|(p, _, t)| p == player && *t == tp.team.name)
+			{
+				Some((_, assists, _)) => *assists += other_assists,
+				None => {
+					tournament_assisters.push((player.to_owned(), *other_assists, tp.team.name))
+				}
 			}
 		}
 	}
 
-	Ok(cup_file_paths)
+	TournamentResult::from(
+		teams_results,
+		group_tables,
+		tournament,
+		tournament_goal_scorers,
+		tournament_assisters,
+	)
 }
@@ -1,28 +1,176 @@
 use serde::{Deserialize, Serialize};
 
+use crate::players::{normalize, PlayerIdentities};
 use crate::tournament::GroupID;
 use common::{
 	errors::{FixtureError, ToolError},
-	TeamName,
+	PlayerName, TeamName,
 };
 
+// Displayed by the same names cup files use.
+#[derive(
+	Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq, strum_macros::Display,
+)]
+pub enum GoalType {
+	#[default]
+	OpenPlay,
+	Penalty,
+	FreeKick,
+	Header,
+	// Scored by a player of the other team, so nobody gets credited for it.
+	OwnGoal,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Goal {
+	pub scorer: PlayerName,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub assister: Option<PlayerName>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub minute: Option<u8>,
+	#[serde(default, rename = "type")]
+	pub goal_type: GoalType,
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Fixture {
 	pub team1: TeamName,
 	pub team2: TeamName,
 	pub score1: u8,
 	pub score2: u8,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub pen1: Option<u8>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub pen2: Option<u8>,
+	// Legacy format. Filled in from goals1 and goals2 when those are used instead.
+	#[serde(default)]
 	pub scorers1: Vec<String>,
+	#[serde(default)]
 	pub scorers2: Vec<String>,
+	#[serde(default)]
 	pub assisters1: Vec<String>,
+	#[serde(default)]
 	pub assisters2: Vec<String>,
 	#[serde(rename = "group_id")]
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub group: Option<GroupID>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub goals1: Option<Vec<Goal>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub goals2: Option<Vec<Goal>>,
+	// The goalkeeper each team fielded, if entered. Otherwise the roster's goalkeepers are
+	// credited with clean sheets.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub keeper1: Option<PlayerName>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub keeper2: Option<PlayerName>,
 }
 
 impl Fixture {
+	pub fn goals(&self, is_team1: bool) -> Option<&Vec<Goal>> {
+		match is_team1 {
+			true => self.goals1.as_ref(),
+			false => self.goals2.as_ref(),
+		}
+	}
+
+	pub fn own_goals(&self, is_team1: bool) -> usize {
+		self.goals(is_team1)
+			.into_iter()
+			.flatten()
+			.filter(|g| g.goal_type == GoalType::OwnGoal)
+			.count()
+	}
+
+	// Scorer–assister pairs of the goals that were assisted.
+	pub fn partnerships(&self, is_team1: bool) -> impl Iterator<Item = (&PlayerName, &PlayerName)> {
+		self.goals(is_team1)
+			.into_iter()
+			.flatten()
+			.filter(|g| g.goal_type != GoalType::OwnGoal)
+			.filter_map(|g| Some((&g.scorer, g.assister.as_ref()?)))
+	}
+
+	// Replace every player name with its canonical name.
+	pub fn resolve_players(&mut self, identities: &PlayerIdentities) {
+		for (team, scorers, assisters, goals, keeper) in [
+			(
+				self.team1,
+				&mut self.scorers1,
+				&mut self.assisters1,
+				&mut self.goals1,
+				&mut self.keeper1,
+			),
+			(
+				self.team2,
+				&mut self.scorers2,
+				&mut self.assisters2,
+				&mut self.goals2,
+				&mut self.keeper2,
+			),
+		] {
+			for name in scorers
+				.iter_mut()
+				.chain(assisters.iter_mut())
+				.chain(keeper.iter_mut())
+			{
+				*name = identities.resolve(name, team);
+			}
+			for goal in goals.iter_mut().flatten() {
+				if let Some(assister) = &mut goal.assister {
+					*assister = identities.resolve(assister, team);
+				}
+				// Own goals are scored by a player of the other team.
+				let scorer_team = match goal.goal_type {
+					GoalType::OwnGoal if team == self.team1 => self.team2,
+					GoalType::OwnGoal => self.team1,
+					_ => team,
+				};
+				goal.scorer = identities.resolve(&goal.scorer, scorer_team);
+			}
+		}
+	}
+
+	// Fill in the legacy scorer and assister lists from the goal events, so the rest of the
+	// stats don't need to care which format was used. Lists that were entered alongside goal
+	// events have to agree with them.
+	pub fn link_goal_events(&mut self) -> Result<(), ToolError> {
+		for is_team1 in [true, false] {
+			let Some(goals) = self.goals(is_team1) else {
+				continue;
+			};
+			let credited = || goals.iter().filter(|g| g.goal_type != GoalType::OwnGoal);
+			let scorers: Vec<PlayerName> = credited().map(|g| g.scorer.clone()).collect();
+			let assisters: Vec<PlayerName> =
+				credited().filter_map(|g| g.assister.clone()).collect();
+
+			let (team, old_scorers, old_assisters) = match is_team1 {
+				true => (self.team1, &mut self.scorers1, &mut self.assisters1),
+				false => (self.team2, &mut self.scorers2, &mut self.assisters2),
+			};
+			// Names are resolved by now, but unknown ones may still differ in case.
+			let sorted = |players: &[PlayerName]| {
+				let mut players: Vec<String> = players.iter().map(|p| normalize(p)).collect();
+				players.sort_unstable();
+				players
+			};
+			if !old_scorers.is_empty() && sorted(old_scorers) != sorted(&scorers)
+				|| !old_assisters.is_empty() && sorted(old_assisters) != sorted(&assisters)
+			{
+				return Err(FixtureError::GoalEventsMismatch(
+					self.team1.to_string(),
+					self.team2.to_string(),
+					team.to_string(),
+				)
+				.into());
+			}
+			*old_scorers = scorers;
+			*old_assisters = assisters;
+		}
+
+		Ok(())
+	}
+
 	pub fn loser(&self) -> Result<Option<TeamName>, ToolError> {
 		match self.winner() {
 			Ok(Some(t)) if t == self.team1 => Ok(Some(self.team2)),
@@ -37,7 +185,7 @@ impl Fixture {
 				return Err(FixtureError::MissingPenalties1(
 					self.team1.to_string(),
 					self.team2.to_string(),
-					pen_goals.clone(),
+					pen_goals,
 				)
 				.into())
 			}
@@ -45,7 +193,7 @@ impl Fixture {
 				return Err(FixtureError::MissingPenalties2(
 					self.team1.to_string(),
 					self.team2.to_string(),
-					pen_goals.clone(),
+					pen_goals,
 				)
 				.into())
 			}
@@ -78,7 +226,7 @@ impl Fixture {
 	}
 }
 
-#[derive(Serialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct GreatestFixture {
 	pub fixture: Fixture,
 	pub tournament_name: String,
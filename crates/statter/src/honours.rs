@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+use crate::team::Team;
+use crate::tournament::Participation;
+use common::TeamName;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Honours {
+	pub titles: u32,
+	pub runner_ups: u32,
+	// Top 3 finishes, so titles and runner-ups are included.
+	pub podiums: u32,
+	pub tournaments_played: u32,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub best_finish: Option<u8>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub worst_finish: Option<u8>,
+}
+
+impl Honours {
+	fn add(&mut self, participation: &Participation) {
+		let placement = participation.placement;
+		match placement {
+			1 => self.titles += 1,
+			2 => self.runner_ups += 1,
+			_ => (),
+		}
+		if placement <= 3 {
+			self.podiums += 1;
+		}
+		self.tournaments_played += 1;
+		self.best_finish = Some(self.best_finish.map_or(placement, |b| b.min(placement)));
+		self.worst_finish = Some(self.worst_finish.map_or(placement, |w| w.max(placement)));
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SeasonHonours {
+	pub season_num: u8,
+	pub honours: Honours,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct TrophyCabinet {
+	pub all_time: Honours,
+	pub seasons: Vec<SeasonHonours>,
+}
+
+impl TrophyCabinet {
+	pub fn from(participations: &[Participation]) -> Self {
+		let mut cabinet = Self::default();
+		for participation in participations {
+			cabinet.all_time.add(participation);
+
+			let season = match cabinet
+				.seasons
+				.iter_mut()
+				.position(|s| s.season_num == participation.season_num)
+			{
+				Some(idx) => &mut cabinet.seasons[idx],
+				None => {
+					cabinet.seasons.push(SeasonHonours {
+						season_num: participation.season_num,
+						honours: Honours::default(),
+					});
+					cabinet.seasons.last_mut().unwrap()
+				}
+			};
+			season.honours.add(participation);
+		}
+
+		cabinet.seasons.sort_unstable_by_key(|s| s.season_num);
+		cabinet
+	}
+}
+
+#[derive(Serialize)]
+pub struct TitlesTableEntry {
+	pub rank: u8,
+	pub team: TeamName,
+	pub titles: u32,
+	pub runner_ups: u32,
+	pub podiums: u32,
+	pub tournaments_played: u32,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub best_finish: Option<u8>,
+}
+
+// All-time honours of every team, ranked by titles, then runner-ups, then podiums.
+#[derive(Serialize)]
+pub struct TitlesTable {
+	pub teams: Vec<TitlesTableEntry>,
+}
+
+impl TitlesTable {
+	pub fn from<'a>(teams: impl Iterator<Item = &'a Team>) -> Self {
+		let mut entries: Vec<TitlesTableEntry> = teams
+			.filter_map(|team| {
+				let honours = &team.trophy_cabinet.as_ref()?.all_time;
+				Some(TitlesTableEntry {
+					rank: 0,
+					team: team.name,
+					titles: honours.titles,
+					runner_ups: honours.runner_ups,
+					podiums: honours.podiums,
+					tournaments_played: honours.tournaments_played,
+					best_finish: honours.best_finish,
+				})
+			})
+			.collect();
+
+		entries.sort_unstable_by(|a, b| {
+			b.titles
+				.cmp(&a.titles)
+				.then(b.runner_ups.cmp(&a.runner_ups))
+				.then(b.podiums.cmp(&a.podiums))
+				.then(a.best_finish.cmp(&b.best_finish))
+				.then(a.team.cmp(&b.team))
+		});
+		for (i, entry) in entries.iter_mut().enumerate() {
+			entry.rank = i as u8 + 1;
+		}
+
+		Self { teams: entries }
+	}
+}
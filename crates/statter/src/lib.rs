@@ -1,5 +1,28 @@
+pub mod bracket;
+pub mod cache;
+pub mod charts;
+pub mod config;
+pub mod defence;
 pub mod entry;
 pub mod fixture;
+pub mod honours;
+pub mod output;
+pub mod overlay;
+pub mod partnerships;
+pub mod players;
 pub mod rankings;
+pub mod records;
+pub mod report;
+pub mod rosters;
+pub mod serve;
+pub mod site;
+pub mod spreadsheet;
+pub mod sqlite;
+pub mod streaks;
+pub mod svg;
 pub mod team;
 pub mod tournament;
+pub mod watch;
+
+#[cfg(test)]
+mod test_support;
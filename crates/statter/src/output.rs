@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use common::errors::{OutputError, ToolError};
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+	#[default]
+	Toml,
+	Json,
+	Yaml,
+}
+
+impl OutputFormat {
+	pub fn extension(&self) -> &'static str {
+		match self {
+			OutputFormat::Toml => "toml",
+			OutputFormat::Json => "json",
+			OutputFormat::Yaml => "yaml",
+		}
+	}
+
+	// Every output file goes through here, so all of them are written in the same format.
+	pub fn to_string<T: Serialize>(&self, value: &T) -> Result<String, ToolError> {
+		match self {
+			OutputFormat::Toml => Ok(toml::to_string(value)?),
+			OutputFormat::Json => {
+				let json = serde_json::to_string_pretty(value)
+					.map_err(|e| OutputError::JsonFailure(e.to_string()))?;
+				Ok(json + "\n")
+			}
+			OutputFormat::Yaml => Ok(serde_norway::to_string(value)
+				.map_err(|e| OutputError::YamlFailure(e.to_string()))?),
+		}
+	}
+}
+
+// For `#[serde(with = "crate::output::date")]` on the dates of output structs. toml's
+// Datetime would serialize to a nested object in JSON and YAML, so dates are written as
+// plain strings like "2024-01-01" in every format. Reading accepts both, so cup files and
+// older caches still parse.
+pub mod date {
+	use std::fmt;
+
+	use serde::de::{self, value::MapAccessDeserializer, MapAccess, Visitor};
+	use serde::{Deserialize, Deserializer, Serializer};
+	use toml::value::Datetime;
+
+	pub fn serialize<S: Serializer>(date: &Datetime, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(date)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Datetime, D::Error> {
+		struct DateVisitor;
+
+		impl<'de> Visitor<'de> for DateVisitor {
+			type Value = Datetime;
+
+			fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+				formatter.write_str("a date")
+			}
+
+			fn visit_str<E: de::Error>(self, date: &str) -> Result<Datetime, E> {
+				date.parse().map_err(E::custom)
+			}
+
+			// A native TOML date.
+			fn visit_map<M: MapAccess<'de>>(self, map: M) -> Result<Datetime, M::Error> {
+				Datetime::deserialize(MapAccessDeserializer::new(map))
+			}
+		}
+
+		deserializer.deserialize_any(DateVisitor)
+	}
+}
@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use image::{Rgb, RgbImage};
+use tokio::fs;
+
+use crate::charts::{draw_text, fill_rect, save_png, team_colour, text_width};
+use crate::fixture::Fixture;
+use crate::players::PlayerIdentities;
+use crate::tournament::{GroupTable, ScheduledFixture, Tournament};
+use common::{errors::ToolError, PlayerName, TeamName};
+
+const SCALE: i32 = 3;
+const PADDING: i32 = 4 * SCALE;
+const LINE_HEIGHT: i32 = 10 * SCALE;
+
+const BACKGROUND: Rgb<u8> = Rgb([24, 24, 32]);
+const HEADING: Rgb<u8> = Rgb([150, 150, 165]);
+const TEXT: Rgb<u8> = Rgb([255, 255, 255]);
+
+// The cup file named in the overlay config, read the way statter reads cups but without
+// running it, since it doesn't have to be finished.
+pub async fn load_live_tournament(
+	path: &Path,
+	identities: &PlayerIdentities,
+) -> Result<Tournament, ToolError> {
+	let cup_string = fs::read_to_string(path).await?;
+	let mut cup: toml::Table = toml::from_str(&cup_string)?;
+	// The winners bracket is only missing before the playoffs, which finished cups are past.
+	if let Some(toml::Value::Table(brackets)) = cup.get_mut("brackets") {
+		brackets
+			.entry("winners")
+			.or_insert_with(|| toml::Value::Array(Vec::new()));
+	}
+	// Written out again rather than converted, since converting a table loses its dates.
+	let mut tournament: Tournament = toml::from_str(&toml::to_string(&cup)?)?;
+	tournament.resolve_players(identities);
+	tournament.link_goal_events()?;
+	Ok(tournament)
+}
+
+// Writes a text file and a PNG of the same lines for each part of the overlay, for OBS text
+// and image sources: "group-a" and so on, "next-fixture", "last-result" and "top-scorer".
+// Parts with nothing to show get an empty text file and no PNG.
+pub async fn write_overlay(tournament: &Tournament, folder: &Path) -> Result<(), ToolError> {
+	if !folder.is_dir() {
+		fs::create_dir_all(folder).await?;
+	}
+
+	let mut group_parts = Vec::new();
+	for group_table in tournament.current_group_tables() {
+		let name = format!("group-{}", group_table.group.to_string().to_lowercase());
+		let (lines, teams) = group_table_lines(&group_table);
+		write_part(folder, &name, &lines, &teams).await?;
+		group_parts.push(name);
+	}
+	// Groups of an earlier cup that this one doesn't have would otherwise stay on screen.
+	let mut entries = fs::read_dir(folder).await?;
+	while let Some(entry) = entries.next_entry().await? {
+		let path = entry.path();
+		let is_stale = path
+			.file_stem()
+			.and_then(|stem| stem.to_str())
+			.is_some_and(|stem| {
+				stem.starts_with("group-") && !group_parts.iter().any(|p| p == stem)
+			});
+		let is_part = matches!(
+			path.extension().and_then(|e| e.to_str()),
+			Some("txt" | "png")
+		);
+		if is_stale && is_part {
+			fs::remove_file(path).await?;
+		}
+	}
+
+	let (next, last) = schedule_progress(tournament);
+	let next_lines: Vec<String> = next
+		.map(|scheduled| match &scheduled.round {
+			Some(round) => format!("{round}: {} vs {}", scheduled.team1, scheduled.team2),
+			None => format!("{} vs {}", scheduled.team1, scheduled.team2),
+		})
+		.into_iter()
+		.collect();
+	write_part(folder, "next-fixture", &next_lines, &[]).await?;
+
+	let last_lines: Vec<String> = last.map(result_line).into_iter().collect();
+	write_part(folder, "last-result", &last_lines, &[]).await?;
+
+	let top_scorer_lines: Vec<String> = top_scorers(tournament)
+		.into_iter()
+		.map(|(player, team, goals)| format!("{player} ({team}): {goals}"))
+		.collect();
+	write_part(folder, "top-scorer", &top_scorer_lines, &[]).await?;
+
+	Ok(())
+}
+
+// Lines of the table, with the team of each line, if any, for its colour marker. The font
+// has fixed-width characters, so the columns line up in the PNG.
+fn group_table_lines(group_table: &GroupTable) -> (Vec<String>, Vec<Option<TeamName>>) {
+	let mut lines = vec![
+		format!("Group {}", group_table.group),
+		format!(
+			"{:<2} {:<16} {:>2} {:>2} {:>2} {:>2} {:>3} {:>3}",
+			"#", "Team", "P", "W", "D", "L", "GD", "Pts"
+		),
+	];
+	let mut teams = vec![None, None];
+	for s in &group_table.standings {
+		lines.push(format!(
+			"{:<2} {:<16} {:>2} {:>2} {:>2} {:>2} {:>3} {:>3}",
+			s.position,
+			s.team.to_string(),
+			s.played,
+			s.won,
+			s.drawn,
+			s.lost,
+			match s.goal_difference {
+				0 => String::from("0"),
+				goal_difference => format!("{goal_difference:+}"),
+			},
+			s.points
+		));
+		teams.push(Some(s.team));
+	}
+	(lines, teams)
+}
+
+fn result_line(fixture: &Fixture) -> String {
+	let mut line = format!(
+		"{} {}-{} {}",
+		fixture.team1, fixture.score1, fixture.score2, fixture.team2
+	);
+	if let (Some(pen1), Some(pen2)) = (fixture.pen1, fixture.pen2) {
+		line += &format!(" ({pen1}-{pen2} p)");
+	}
+	line
+}
+
+// The next scheduled fixture that hasn't been played, and the last fixture that has. Each
+// fixture entered is matched with the first scheduled fixture between the same two teams,
+// either way round. Fixtures that weren't scheduled count as played after the scheduled
+// ones.
+fn schedule_progress(tournament: &Tournament) -> (Option<&ScheduledFixture>, Option<&Fixture>) {
+	let pair = |a: TeamName, b: TeamName| (a.min(b), a.max(b));
+	let mut unmatched: Vec<&Fixture> = tournament.fixtures().collect();
+	let mut last = None;
+	let mut next = None;
+	for scheduled in tournament.schedule.iter().flatten() {
+		let scheduled_pair = pair(scheduled.team1, scheduled.team2);
+		match unmatched
+			.iter()
+			.position(|f| pair(f.team1, f.team2) == scheduled_pair)
+		{
+			Some(idx) => last = Some(unmatched.remove(idx)),
+			None => {
+				next = Some(scheduled);
+				break;
+			}
+		}
+	}
+	if next.is_some() {
+		return (next, last);
+	}
+	(None, unmatched.last().copied().or(last))
+}
+
+// Everyone tied for the most goals, by name.
+fn top_scorers(tournament: &Tournament) -> Vec<(PlayerName, TeamName, u32)> {
+	let mut goals: HashMap<(&PlayerName, TeamName), u32> = HashMap::new();
+	for fixture in tournament.fixtures() {
+		for (team, scorers) in [
+			(fixture.team1, &fixture.scorers1),
+			(fixture.team2, &fixture.scorers2),
+		] {
+			for scorer in scorers {
+				*goals.entry((scorer, team)).or_default() += 1;
+			}
+		}
+	}
+
+	let most = goals.values().copied().max().unwrap_or(0);
+	let mut top_scorers: Vec<(PlayerName, TeamName, u32)> = goals
+		.into_iter()
+		.filter(|(_, count)| *count == most)
+		.map(|((player, team), count)| (player.clone(), team, count))
+		.collect();
+	top_scorers.sort_unstable_by(|(a_name, a_team, _), (b_name, b_team, _)| {
+		a_name
+			.to_lowercase()
+			.cmp(&b_name.to_lowercase())
+			.then(a_team.cmp(b_team))
+	});
+	top_scorers
+}
+
+// Replaces both files, so OBS never shows an old PNG next to new text.
+async fn write_part(
+	folder: &Path,
+	name: &str,
+	lines: &[String],
+	teams: &[Option<TeamName>],
+) -> Result<(), ToolError> {
+	fs::write(folder.join(format!("{name}.txt")), lines.join("\n")).await?;
+	let png_path = folder.join(format!("{name}.png"));
+	match lines.is_empty() {
+		true if png_path.is_file() => fs::remove_file(png_path).await?,
+		true => (),
+		false => save_png(panel(lines, teams), png_path).await?,
+	}
+	Ok(())
+}
+
+// The lines on a dark panel. Lines without a team are drawn as headings, unless no line has
+// a team. Lines with a team get a marker in its colour.
+fn panel(lines: &[String], teams: &[Option<TeamName>]) -> RgbImage {
+	let marker = match teams.is_empty() {
+		true => 0,
+		false => 4 * SCALE,
+	};
+	let width = lines
+		.iter()
+		.map(|line| text_width(line, SCALE))
+		.max()
+		.unwrap_or(0)
+		+ marker
+		+ 2 * PADDING;
+	let height = lines.len() as i32 * LINE_HEIGHT + 2 * PADDING - 3 * SCALE;
+	let mut img = RgbImage::from_pixel(width as u32, height as u32, BACKGROUND);
+
+	for (i, line) in lines.iter().enumerate() {
+		let y = PADDING + i as i32 * LINE_HEIGHT;
+		let colour = match teams.get(i) {
+			Some(Some(team)) => {
+				fill_rect(
+					&mut img,
+					PADDING,
+					y,
+					2 * SCALE,
+					7 * SCALE,
+					team_colour(*team),
+				);
+				TEXT
+			}
+			Some(None) => HEADING,
+			None => TEXT,
+		};
+		draw_text(&mut img, PADDING + marker, y, line, SCALE, colour);
+	}
+	img
+}
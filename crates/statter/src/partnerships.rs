@@ -0,0 +1,57 @@
+use serde::Serialize;
+
+use crate::team::Team;
+use common::{PlayerName, TeamName};
+
+const PARTNERSHIPS_IN_TABLE: usize = 20;
+
+#[derive(Serialize)]
+pub struct PartnershipEntry {
+	pub scorer: PlayerName,
+	pub assister: PlayerName,
+	pub team: TeamName,
+	pub goals: u32,
+}
+
+// Top scorer–assister duos across all tournaments. Only fixtures with goal events count,
+// since the legacy lists don't say who assisted whom.
+#[derive(Serialize)]
+pub struct Partnerships {
+	pub partnerships: Vec<PartnershipEntry>,
+}
+
+impl Partnerships {
+	pub fn from<'a>(teams: impl Iterator<Item = &'a Team>) -> Self {
+		let mut partnerships: Vec<PartnershipEntry> = teams
+			.flat_map(|team| {
+				team.partnerships
+					.iter()
+					.map(|(scorer, assister, goals)| PartnershipEntry {
+						scorer: scorer.clone(),
+						assister: assister.clone(),
+						team: team.name,
+						goals: *goals,
+					})
+			})
+			.collect();
+
+		partnerships.sort_unstable_by(|a, b| {
+			b.goals
+				.cmp(&a.goals)
+				.then(
+					a.scorer
+						.to_ascii_lowercase()
+						.cmp(&b.scorer.to_ascii_lowercase()),
+				)
+				.then(
+					a.assister
+						.to_ascii_lowercase()
+						.cmp(&b.assister.to_ascii_lowercase()),
+				)
+				.then(a.team.cmp(&b.team))
+		});
+		partnerships.truncate(PARTNERSHIPS_IN_TABLE);
+
+		Self { partnerships }
+	}
+}
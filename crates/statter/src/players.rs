@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+use tokio::fs;
+
+use crate::rosters::TeamRosters;
+use crate::team::Team;
+use common::{
+	errors::{ConfigError, ToolError},
+	PlayerName, TeamName,
+};
+
+// One real player, however their name was typed in the cup files.
+#[derive(Deserialize)]
+pub struct PlayerIdentity {
+	pub id: String,
+	// Name used in the stats. Defaults to the ID.
+	pub name: Option<PlayerName>,
+	#[serde(default)]
+	pub aliases: Vec<PlayerName>,
+	// Limits the aliases to this team's fixtures, for players sharing a name across teams.
+	pub team: Option<TeamName>,
+	// Player ID on the team's lineupper roster. Needs team and a roster folder, and makes the
+	// roster name another alias.
+	pub lineupper_id: Option<u8>,
+}
+
+impl PlayerIdentity {
+	pub fn display_name(&self) -> &str {
+		self.name.as_deref().unwrap_or(&self.id)
+	}
+}
+
+#[derive(Deserialize)]
+struct IdentityFile {
+	players: Vec<PlayerIdentity>,
+}
+
+#[derive(Default)]
+pub struct PlayerIdentities {
+	players: Vec<PlayerIdentity>,
+	// Normalized alias to the indices of the players using it.
+	aliases: HashMap<String, Vec<usize>>,
+	// Everything the resolved names depend on, so cached cups can be invalidated.
+	fingerprint: String,
+}
+
+impl PlayerIdentities {
+	pub async fn load(path: &Path, rosters_folder: Option<&Path>) -> Result<Self, ToolError> {
+		let identities_string = fs::read_to_string(path).await?;
+		let identity_file: IdentityFile = toml::from_str(&identities_string).map_err(|e| {
+			ConfigError::InvalidIdentities(path.display().to_string(), e.to_string())
+		})?;
+
+		// Only the rosters of players with a lineupper ID are needed.
+		let rosters = match rosters_folder {
+			Some(folder) => {
+				let mut teams: Vec<TeamName> = identity_file
+					.players
+					.iter()
+					.filter(|p| p.lineupper_id.is_some())
+					.filter_map(|p| p.team)
+					.collect();
+				teams.sort_unstable();
+				teams.dedup();
+				Some(TeamRosters::load(folder, teams.into_iter()).await?)
+			}
+			None => None,
+		};
+
+		let mut identities = Self {
+			players: identity_file.players,
+			aliases: HashMap::new(),
+			fingerprint: identities_string,
+		};
+		for idx in 0..identities.players.len() {
+			let player = &identities.players[idx];
+			if identities.players[..idx].iter().any(|p| p.id == player.id) {
+				return Err(ConfigError::DuplicatePlayerID(player.id.clone()).into());
+			}
+
+			let mut names = vec![player.id.clone(), player.display_name().to_string()];
+			names.extend(player.aliases.iter().cloned());
+			if let (Some(team), Some(lineupper_id), Some(rosters)) =
+				(player.team, player.lineupper_id, &rosters)
+			{
+				let roster_name = rosters.get(team).and_then(|r| {
+					r.active
+						.iter()
+						.chain(&r.reserve)
+						.find(|p| p.id == lineupper_id)
+						.map(|p| p.name.clone())
+				});
+				match roster_name {
+					Some(roster_name) => {
+						identities.fingerprint += &format!("\n{team}:{lineupper_id}:{roster_name}");
+						names.push(roster_name);
+					}
+					None => eprintln!(
+						"ATTENTION: '{}' has lineupper ID {lineupper_id}, but it's not on the {team} roster.",
+						player.id
+					),
+				}
+			}
+
+			for name in names {
+				identities.add_alias(&name, idx)?;
+			}
+		}
+
+		Ok(identities)
+	}
+
+	pub fn fingerprint(&self) -> &str {
+		&self.fingerprint
+	}
+
+	// Canonical name of the player, or the name as-is if it isn't a known alias. Aliases
+	// tied to the team win over ones that aren't tied to any team.
+	pub fn resolve(&self, name: &str, team: TeamName) -> PlayerName {
+		let Some(candidates) = self.aliases.get(&normalize(name)) else {
+			return name.to_string();
+		};
+
+		candidates
+			.iter()
+			.map(|&idx| &self.players[idx])
+			.filter(|p| p.team.is_none_or(|t| t == team))
+			.max_by_key(|p| p.team.is_some())
+			.map_or(name.to_string(), |p| p.display_name().to_string())
+	}
+
+	fn add_alias(&mut self, alias: &str, idx: usize) -> Result<(), ToolError> {
+		let team = self.players[idx].team;
+		let users = self.aliases.entry(normalize(alias)).or_default();
+		if users.contains(&idx) {
+			return Ok(());
+		}
+		if let Some(&other) = users
+			.iter()
+			.find(|&&other| self.players[other].team == team)
+		{
+			return Err(ConfigError::AmbiguousAlias(
+				alias.to_string(),
+				self.players[other].id.clone(),
+				self.players[idx].id.clone(),
+			)
+			.into());
+		}
+		users.push(idx);
+		Ok(())
+	}
+}
+
+// Aliases match regardless of case and surrounding whitespace.
+pub(crate) fn normalize(name: &str) -> String {
+	name.trim().to_lowercase()
+}
+
+// Names of the same team that are probably the same player, e.g. "Del Piero" and
+// "Delpiero", or "Pirlo" and "Pirllo".
+pub fn near_duplicates(team: &Team) -> Vec<(PlayerName, PlayerName)> {
+	let mut names: Vec<&PlayerName> = team
+		.scorers
+		.iter()
+		.chain(&team.assisters)
+		.map(|(name, _)| name)
+		.collect();
+	names.sort_unstable();
+	names.dedup();
+
+	let squash = |name: &str| -> Vec<char> {
+		name.chars()
+			.filter(|c| c.is_alphanumeric())
+			.flat_map(char::to_lowercase)
+			.collect()
+	};
+	let mut duplicates = Vec::new();
+	for (i, a) in names.iter().enumerate() {
+		for b in &names[i + 1..] {
+			let (a_squashed, b_squashed) = (squash(a), squash(b));
+			let min_len = a_squashed.len().min(b_squashed.len());
+			// Names more than a character apart in length can't be one edit apart, which
+			// skips the edit distance for most pairs.
+			let len_diff = a_squashed.len().abs_diff(b_squashed.len());
+			if a_squashed == b_squashed
+				|| min_len >= 5 && len_diff <= 1 && edit_distance(&a_squashed, &b_squashed) <= 1
+			{
+				duplicates.push(((*a).clone(), (*b).clone()));
+			}
+		}
+	}
+	duplicates
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+	let mut previous: Vec<usize> = (0..=b.len()).collect();
+	for (i, ca) in a.iter().enumerate() {
+		let mut current = vec![i + 1];
+		for (j, cb) in b.iter().enumerate() {
+			let substitution = previous[j] + (ca != cb) as usize;
+			current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+		}
+		previous = current;
+	}
+	previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::entry::run_cup;
+	use crate::test_support::{cup, identities};
+
+	fn team_with(names: &[&str]) -> Team {
+		let mut team = Team::from(TeamName::Cursed);
+		team.scorers = names.iter().map(|name| (name.to_string(), 1)).collect();
+		team
+	}
+
+	#[test]
+	fn aliases_ignore_case_and_whitespace() {
+		let identities = identities(
+			r#"
+[[players]]
+id = "del-piero"
+name = "Del Piero"
+aliases = ["DelPiero"]
+"#,
+		)
+		.unwrap();
+
+		assert_eq!(
+			identities.resolve("  delpiero ", TeamName::Cursed),
+			"Del Piero"
+		);
+		assert_eq!(
+			identities.resolve("DEL-PIERO", TeamName::Cursed),
+			"Del Piero"
+		);
+		assert_eq!(
+			identities.resolve("Del Pierro", TeamName::Cursed),
+			"Del Pierro"
+		);
+	}
+
+	#[test]
+	fn team_aliases_win() {
+		let identities = identities(
+			r#"
+[[players]]
+id = "toni-cursed"
+name = "Toni"
+aliases = ["T"]
+team = "Cursed"
+
+[[players]]
+id = "toni-moai"
+name = "Tony"
+aliases = ["T"]
+"#,
+		)
+		.unwrap();
+
+		assert_eq!(identities.resolve("t", TeamName::Cursed), "Toni");
+		assert_eq!(identities.resolve("t", TeamName::Moai), "Tony");
+	}
+
+	#[test]
+	fn ambiguous_alias_fails() {
+		let identities = identities(
+			r#"
+[[players]]
+id = "kaka"
+aliases = ["Ricky"]
+
+[[players]]
+id = "ricardo"
+aliases = [" ricky"]
+"#,
+		);
+
+		assert!(matches!(
+			identities,
+			Err(ToolError::ConfigError(ConfigError::AmbiguousAlias(..)))
+		));
+	}
+
+	#[test]
+	fn near_duplicates_within_one_edit() {
+		let team = team_with(&[
+			"Del Piero",
+			"Delpiero",
+			"Pirlo",
+			"Pirllo",
+			"Buffon",
+			"Bufflo",
+		]);
+
+		assert_eq!(
+			near_duplicates(&team),
+			[
+				(String::from("Del Piero"), String::from("Delpiero")),
+				(String::from("Pirllo"), String::from("Pirlo")),
+			]
+		);
+	}
+
+	#[test]
+	fn short_names_are_not_near_duplicates() {
+		let team = team_with(&["Toni", "Tony", "Kaka", "kaka!"]);
+
+		assert_eq!(
+			near_duplicates(&team),
+			[(String::from("Kaka"), String::from("kaka!"))]
+		);
+	}
+
+	#[test]
+	fn goal_events_match_scorers_by_identity() {
+		let identities = identities(
+			r#"
+[[players]]
+id = "del-piero"
+name = "Del Piero"
+aliases = ["DelPiero"]
+"#,
+		)
+		.unwrap();
+		let fixture = r#"[[brackets.winners]]
+team1 = "Cursed"
+team2 = "Moai"
+score1 = 2
+score2 = 0
+scorers1 = ["DelPiero", "pirlo"]
+scorers2 = []
+assisters1 = []
+assisters2 = []
+goals1 = [{ scorer = "Del Piero" }, { scorer = "Pirlo" }]
+
+"#;
+
+		let result = run_cup(
+			toml::from_str(&cup(2, false, &[fixture.to_string()])).unwrap(),
+			&identities,
+		);
+		assert!(result.is_ok());
+	}
+}
@@ -13,9 +13,10 @@ pub struct RankedTeam {
 
 #[derive(Serialize)]
 pub struct SeasonRankings {
+	#[serde(with = "crate::output::date")]
 	pub date: Datetime,
 	pub season_num: u8,
-	rankings: Vec<RankedTeam>,
+	pub rankings: Vec<RankedTeam>,
 	pub tournaments: Vec<String>,
 }
 
@@ -25,7 +26,7 @@ pub struct Seasons {
 }
 
 impl Seasons {
-	pub fn from(tourny_results: Vec<TournamentResult>) -> Seasons {
+	pub fn from(tourny_results: &[TournamentResult]) -> Seasons {
 		let mut seasons = Seasons::new();
 		for tr in tourny_results {
 			seasons.push(tr);
@@ -34,13 +35,28 @@ impl Seasons {
 		seasons
 	}
 
+	// A team's final ranking points and rank in a season.
+	pub fn team_standing(&self, season_num: u8, team: TeamName) -> Option<(u32, u8)> {
+		let ranked_team = self
+			.seasons
+			.iter()
+			.find(|s| s.season_num == season_num)?
+			.rankings
+			.iter()
+			.find(|r| r.name == team)?;
+		Some((
+			*ranked_team.ranking_points.last()?,
+			*ranked_team.ranks.last()?,
+		))
+	}
+
 	fn new() -> Self {
 		Self {
 			seasons: Vec::new(),
 		}
 	}
 
-	fn push(&mut self, tourny_result: TournamentResult) {
+	fn push(&mut self, tourny_result: &TournamentResult) {
 		let season_num = tourny_result.season_num;
 
 		// Get correct season for the tournament result.
@@ -0,0 +1,231 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use serde::Serialize;
+use toml::value::Datetime;
+
+use crate::bracket::{Round, Stage};
+use crate::fixture::Fixture;
+use crate::tournament::TournamentResult;
+use common::{PlayerName, TeamName};
+
+const RECORDS_PER_TABLE: usize = 10;
+
+// Points at the exact fixture a record was set in.
+#[derive(Clone, Serialize, PartialEq)]
+pub struct FixtureRef {
+	pub tournament_name: String,
+	#[serde(with = "crate::output::date")]
+	pub date: Datetime,
+	pub stage: Stage,
+	pub round: String,
+	pub team1: TeamName,
+	pub team2: TeamName,
+	pub score1: u8,
+	pub score2: u8,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub pen1: Option<u8>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub pen2: Option<u8>,
+}
+
+impl FixtureRef {
+	pub fn from(fixture: &Fixture, round: &Round, tourny_result: &TournamentResult) -> Self {
+		Self {
+			tournament_name: tourny_result.tournament_name.clone(),
+			date: tourny_result.date,
+			stage: round.stage,
+			round: round.name.clone(),
+			team1: fixture.team1,
+			team2: fixture.team2,
+			score1: fixture.score1,
+			score2: fixture.score2,
+			pen1: fixture.pen1,
+			pen2: fixture.pen2,
+		}
+	}
+
+	fn margin(&self) -> u8 {
+		self.score1.abs_diff(self.score2)
+	}
+
+	// Widened, since two u8 scores can add up to more than a u8 holds.
+	fn total_goals(&self) -> u16 {
+		u16::from(self.score1) + u16::from(self.score2)
+	}
+
+	fn total_penalties(&self) -> u16 {
+		u16::from(self.pen1.unwrap_or_default()) + u16::from(self.pen2.unwrap_or_default())
+	}
+}
+
+#[derive(Serialize, PartialEq)]
+pub struct PlayerFixtureRecord {
+	pub player: PlayerName,
+	pub team: TeamName,
+	pub goals: u32,
+	pub fixture: FixtureRef,
+}
+
+#[derive(Serialize, PartialEq)]
+pub struct PlayerTournamentRecord {
+	pub player: PlayerName,
+	pub team: TeamName,
+	pub goals: u32,
+	pub tournament_name: String,
+	#[serde(with = "crate::output::date")]
+	pub date: Datetime,
+}
+
+#[derive(Serialize)]
+pub struct TeamTournamentsRecord {
+	pub team: TeamName,
+	pub count: u32,
+	pub tournaments: Vec<String>,
+}
+
+// Best performances across every tournament played.
+#[derive(Serialize)]
+pub struct Records {
+	pub biggest_wins: Vec<FixtureRef>,
+	pub highest_scoring_fixtures: Vec<FixtureRef>,
+	pub longest_shootouts: Vec<FixtureRef>,
+	pub most_goals_in_fixture: Vec<PlayerFixtureRecord>,
+	pub most_goals_in_tournament: Vec<PlayerTournamentRecord>,
+	pub most_titles: Vec<TeamTournamentsRecord>,
+	pub most_consecutive_finals: Vec<TeamTournamentsRecord>,
+}
+
+impl Records {
+	// NOTE: Expects the tournament results to be sorted by date, so earlier records win ties.
+	pub fn from(tourny_results: &[TournamentResult]) -> Self {
+		let mut fixtures = Vec::new();
+		let mut player_fixtures = Vec::new();
+		for tr in tourny_results {
+			for round in &tr.rounds {
+				for fixture in &round.fixtures {
+					let fixture_ref = FixtureRef::from(fixture, round, tr);
+					for (team, scorers) in [
+						(fixture.team1, &fixture.scorers1),
+						(fixture.team2, &fixture.scorers2),
+					] {
+						let mut goals: Vec<(&PlayerName, u32)> = Vec::new();
+						for scorer in scorers {
+							match goals.iter_mut().find(|(p, _)| *p == scorer) {
+								Some((_, g)) => *g += 1,
+								None => goals.push((scorer, 1)),
+							}
+						}
+						player_fixtures.extend(goals.into_iter().map(|(player, goals)| {
+							PlayerFixtureRecord {
+								player: player.clone(),
+								team,
+								goals,
+								fixture: fixture_ref.clone(),
+							}
+						}));
+					}
+					fixtures.push(fixture_ref);
+				}
+			}
+		}
+
+		let top_fixtures = |key: &dyn Fn(&FixtureRef) -> (u16, u16)| {
+			let mut top: Vec<FixtureRef> =
+				fixtures.iter().filter(|f| key(f).0 > 0).cloned().collect();
+			top.sort_by_key(|f| Reverse(key(f)));
+			top.truncate(RECORDS_PER_TABLE);
+			top
+		};
+
+		player_fixtures.sort_by_key(|p| Reverse(p.goals));
+		player_fixtures.truncate(RECORDS_PER_TABLE);
+
+		let mut player_tournaments: Vec<PlayerTournamentRecord> = tourny_results
+			.iter()
+			.flat_map(|tr| {
+				tr.scorers
+					.iter()
+					.map(|(player, goals, team)| PlayerTournamentRecord {
+						player: player.clone(),
+						team: *team,
+						goals: *goals,
+						tournament_name: tr.tournament_name.clone(),
+						date: tr.date,
+					})
+			})
+			.collect();
+		player_tournaments.sort_by_key(|p| Reverse(p.goals));
+		player_tournaments.truncate(RECORDS_PER_TABLE);
+
+		Self {
+			biggest_wins: top_fixtures(&|f| (u16::from(f.margin()), f.total_goals())),
+			highest_scoring_fixtures: top_fixtures(&|f| (f.total_goals(), u16::from(f.margin()))),
+			longest_shootouts: top_fixtures(&|f| (f.total_penalties(), 0)),
+			most_goals_in_fixture: player_fixtures,
+			most_goals_in_tournament: player_tournaments,
+			most_titles: Self::most_titles(tourny_results),
+			most_consecutive_finals: Self::most_consecutive_finals(tourny_results),
+		}
+	}
+
+	fn most_titles(tourny_results: &[TournamentResult]) -> Vec<TeamTournamentsRecord> {
+		let mut titles: HashMap<TeamName, Vec<String>> = HashMap::new();
+		for tr in tourny_results {
+			for tp in tr
+				.team_placements
+				.iter()
+				.filter(|tp| tp.placement == Some(1))
+			{
+				titles
+					.entry(tp.team.name)
+					.or_default()
+					.push(tr.tournament_name.clone());
+			}
+		}
+
+		Self::sorted_team_records(titles)
+	}
+
+	// A final is reached by finishing 1st or 2nd. Missing a tournament breaks the streak.
+	fn most_consecutive_finals(tourny_results: &[TournamentResult]) -> Vec<TeamTournamentsRecord> {
+		let mut current: HashMap<TeamName, Vec<String>> = HashMap::new();
+		let mut longest: HashMap<TeamName, Vec<String>> = HashMap::new();
+		for tr in tourny_results {
+			let finalists: Vec<TeamName> = tr
+				.team_placements
+				.iter()
+				.filter(|tp| matches!(tp.placement, Some(1 | 2)))
+				.map(|tp| tp.team.name)
+				.collect();
+
+			current.retain(|team, _| finalists.contains(team));
+			for team in finalists {
+				let streak = current.entry(team).or_default();
+				streak.push(tr.tournament_name.clone());
+				let best = longest.entry(team).or_default();
+				if streak.len() > best.len() {
+					*best = streak.clone();
+				}
+			}
+		}
+
+		Self::sorted_team_records(longest)
+	}
+
+	fn sorted_team_records(
+		team_tournaments: HashMap<TeamName, Vec<String>>,
+	) -> Vec<TeamTournamentsRecord> {
+		let mut records: Vec<TeamTournamentsRecord> = team_tournaments
+			.into_iter()
+			.map(|(team, tournaments)| TeamTournamentsRecord {
+				team,
+				count: tournaments.len() as u32,
+				tournaments,
+			})
+			.collect();
+		records.sort_unstable_by(|a, b| b.count.cmp(&a.count).then(a.team.cmp(&b.team)));
+		records.truncate(RECORDS_PER_TABLE);
+		records
+	}
+}
@@ -0,0 +1,344 @@
+use crate::bracket::{ordinal, Stage};
+use crate::fixture::Fixture;
+use crate::rankings::Seasons;
+use crate::records::{FixtureRef, Records, TeamTournamentsRecord};
+use crate::tournament::TournamentResult;
+use common::TeamName;
+
+const TOP_SCORERS: usize = 5;
+
+// Markdown summary of the tournament at idx, ready to paste into a wiki or Discord.
+// NOTE: Expects the tournament results to be sorted by date, with sorted scorers.
+pub fn tournament_report(
+	tourny_results: &[TournamentResult],
+	idx: usize,
+	seasons: &Seasons,
+) -> String {
+	let tr = &tourny_results[idx];
+	let mut report = format!(
+		"# {}\n\n*Season {}, {}*\n\n",
+		tr.tournament_name, tr.season_num, tr.date
+	);
+
+	report += "## Podium\n\n";
+	let mut placements: Vec<_> = tr.team_placements.iter().collect();
+	placements.sort_by_key(|tp| tp.placement);
+	for tp in placements
+		.iter()
+		.filter(|tp| matches!(tp.placement, Some(1..=3)))
+	{
+		let placement = tp.placement.map_or(String::new(), ordinal);
+		report += &format!("- **{placement}** {}\n", tp.team.name);
+	}
+	report += "\n";
+
+	for group_table in tr.group_tables.iter().flatten() {
+		report += &format!("## Group {}\n\n", group_table.group);
+		report += "| # | Team | P | W | D | L | Goals | GD | Pts | |\n";
+		report += "|---|---|---|---|---|---|---|---|---|---|\n";
+		for s in &group_table.standings {
+			report += &format!(
+				"| {} | {} | {} | {} | {} | {} | {}-{} | {} | {} | {} |\n",
+				s.position,
+				s.team,
+				s.played,
+				s.won,
+				s.drawn,
+				s.lost,
+				s.goals_for,
+				s.goals_against,
+				s.goal_difference,
+				s.points,
+				s.status
+			);
+		}
+		report += "\n";
+	}
+
+	report += "## Results\n\n";
+	for round in &tr.rounds {
+		let round_name = match round.stage {
+			Stage::Groups => round.name.clone(),
+			Stage::GrandFinal => Stage::GrandFinal.to_string(),
+			stage => format!("{stage} – {}", round.name),
+		};
+		report += &format!("### {round_name}\n\n");
+		for fixture in &round.fixtures {
+			report += &format!("- {}\n", fixture_line(fixture));
+		}
+		report += "\n";
+	}
+
+	report += "## Top scorers\n\n";
+	// Everyone tied with the last one shown is shown too.
+	let cutoff = tr
+		.scorers
+		.get(TOP_SCORERS - 1)
+		.map_or(0, |(_, goals, _)| *goals);
+	let top_scorers: Vec<_> = tr
+		.scorers
+		.iter()
+		.filter(|(_, goals, _)| *goals >= cutoff)
+		.collect();
+	match top_scorers.is_empty() {
+		true => report += "No goals were credited.\n",
+		false => {
+			for (player, goals, team) in top_scorers {
+				report += &format!("- {player} ({team}): {goals}\n");
+			}
+		}
+	}
+	report += "\n";
+
+	let records_broken = records_broken(tourny_results, idx);
+	if !records_broken.is_empty() {
+		report += "## Records broken\n\n";
+		for record in records_broken {
+			report += &format!("- {record}\n");
+		}
+		report += "\n";
+	}
+
+	report += &ranking_movement(tourny_results, idx, seasons);
+	report
+}
+
+fn fixture_line(fixture: &Fixture) -> String {
+	let winner = fixture.winner().ok().flatten();
+	let team = |name: TeamName| match winner == Some(name) {
+		true => format!("**{name}**"),
+		false => name.to_string(),
+	};
+	let mut line = format!(
+		"{} {}-{} {}",
+		team(fixture.team1),
+		fixture.score1,
+		fixture.score2,
+		team(fixture.team2)
+	);
+	if let (Some(pen1), Some(pen2)) = (fixture.pen1, fixture.pen2) {
+		line += &format!(" ({pen1}-{pen2} p)");
+	}
+	line
+}
+
+fn fixture_ref_line(f: &FixtureRef) -> String {
+	let mut line = format!("{} {}-{} {}", f.team1, f.score1, f.score2, f.team2);
+	if let (Some(pen1), Some(pen2)) = (f.pen1, f.pen2) {
+		line += &format!(" ({pen1}-{pen2} p)");
+	}
+	format!("{line}, {}", f.tournament_name)
+}
+
+// All-time records taken over by this tournament. Earlier records win ties, so the top
+// record only changes when this tournament beats it, not when it's equalled. Comparing
+// records rather than tournament names keeps cups with the same name apart. The first
+// tournament has no records to break.
+fn records_broken(tourny_results: &[TournamentResult], idx: usize) -> Vec<String> {
+	if idx == 0 {
+		return Vec::new();
+	}
+	let before = Records::from(&tourny_results[..idx]);
+	let after = Records::from(&tourny_results[..=idx]);
+	let mut broken = Vec::new();
+
+	for (title, before, after) in [
+		("Biggest win", &before.biggest_wins, &after.biggest_wins),
+		(
+			"Highest scoring fixture",
+			&before.highest_scoring_fixtures,
+			&after.highest_scoring_fixtures,
+		),
+		(
+			"Longest shoot-out",
+			&before.longest_shootouts,
+			&after.longest_shootouts,
+		),
+	] {
+		if let Some(new) = after.first().filter(|&f| before.first() != Some(f)) {
+			let old = before
+				.first()
+				.map_or(String::from("none"), fixture_ref_line);
+			broken.push(format!(
+				"{title}: {} (previously {old})",
+				fixture_ref_line(new)
+			));
+		}
+	}
+
+	if let Some(new) = after
+		.most_goals_in_fixture
+		.first()
+		.filter(|&p| before.most_goals_in_fixture.first() != Some(p))
+	{
+		let old = before
+			.most_goals_in_fixture
+			.first()
+			.map_or(String::from("none"), |p| {
+				format!("{} ({}) with {}", p.player, p.team, p.goals)
+			});
+		broken.push(format!(
+			"Most goals in a fixture: {} ({}) with {} against {} (previously {old})",
+			new.player,
+			new.team,
+			new.goals,
+			match new.team == new.fixture.team1 {
+				true => new.fixture.team2,
+				false => new.fixture.team1,
+			}
+		));
+	}
+
+	if let Some(new) = after
+		.most_goals_in_tournament
+		.first()
+		.filter(|&p| before.most_goals_in_tournament.first() != Some(p))
+	{
+		let old = before
+			.most_goals_in_tournament
+			.first()
+			.map_or(String::from("none"), |p| {
+				format!(
+					"{} ({}) with {}, {}",
+					p.player, p.team, p.goals, p.tournament_name
+				)
+			});
+		broken.push(format!(
+			"Most goals in a tournament: {} ({}) with {} (previously {old})",
+			new.player, new.team, new.goals
+		));
+	}
+
+	for (title, before, after) in [
+		("Most titles", &before.most_titles, &after.most_titles),
+		(
+			"Most consecutive finals",
+			&before.most_consecutive_finals,
+			&after.most_consecutive_finals,
+		),
+	] {
+		if let Some(new) = team_record_broken(before, after) {
+			let old = before.first().map_or(String::from("none"), |r| {
+				format!("{} with {}", r.team, r.count)
+			});
+			broken.push(format!(
+				"{title}: {} with {} (previously {old})",
+				new.team, new.count
+			));
+		}
+	}
+
+	broken
+}
+
+// Team records are ordered by count, then by team, so ties don't favour the earlier record
+// and it has to be beaten outright. Only this tournament can have raised the count.
+fn team_record_broken<'a>(
+	before: &[TeamTournamentsRecord],
+	after: &'a [TeamTournamentsRecord],
+) -> Option<&'a TeamTournamentsRecord> {
+	let new = after.first()?;
+	let old_count = before.first().map_or(0, |r| r.count);
+	(new.count > old_count).then_some(new)
+}
+
+// Season standings after the tournament, compared to before it.
+fn ranking_movement(tourny_results: &[TournamentResult], idx: usize, seasons: &Seasons) -> String {
+	let tr = &tourny_results[idx];
+	let Some(season) = seasons
+		.seasons
+		.iter()
+		.find(|s| s.season_num == tr.season_num)
+	else {
+		return String::new();
+	};
+	let season_results: Vec<&TournamentResult> = tourny_results
+		.iter()
+		.filter(|r| r.season_num == tr.season_num)
+		.collect();
+	// Position in the season by index, so cups with the same name aren't mixed up.
+	let position = tourny_results[..idx]
+		.iter()
+		.filter(|r| r.season_num == tr.season_num)
+		.count();
+
+	let mut standings: Vec<(u8, Option<u8>, TeamName, u32)> = season
+		.rankings
+		.iter()
+		.filter_map(|r| {
+			let rank = *r.ranks.get(position)?;
+			// Not ranked yet.
+			if rank == 0 {
+				return None;
+			}
+			let previous = match position {
+				0 => None,
+				_ => r.ranks.get(position - 1).copied().filter(|&rank| rank > 0),
+			};
+			let points = season_results[..=position]
+				.iter()
+				.filter_map(|sr| {
+					sr.get_teams_ranked()
+						.into_iter()
+						.find(|rt| rt.name == r.name)?
+						.ranking_points
+						.last()
+						.copied()
+				})
+				.sum();
+			Some((rank, previous, r.name, points))
+		})
+		.collect();
+	standings.sort_unstable_by_key(|(rank, ..)| *rank);
+
+	let mut section = format!("## Season {} rankings\n\n", tr.season_num);
+	section += "| # | Team | Change | Points |\n|---|---|---|---|\n";
+	for (rank, previous, team, points) in standings {
+		let change = match previous {
+			None => String::from("new"),
+			Some(previous) if previous > rank => format!("▲ {}", previous - rank),
+			Some(previous) if previous < rank => format!("▼ {}", rank - previous),
+			Some(_) => String::from("–"),
+		};
+		section += &format!("| {rank} | {team} | {change} | {points} |\n");
+	}
+	section
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_support::{cup, fixture, run};
+
+	#[test]
+	fn records_are_told_apart_from_a_cup_with_the_same_name() {
+		let tourny_results = [
+			run(&cup(
+				2,
+				false,
+				&[fixture("brackets.winners", "Cursed", "Moai", 5, 0)],
+			)),
+			run(&cup(
+				2,
+				false,
+				&[fixture("brackets.winners", "Cursed", "Moai", 1, 0)],
+			)),
+			run(&cup(
+				2,
+				false,
+				&[fixture("brackets.winners", "Moai", "Cursed", 6, 0)],
+			)),
+		];
+
+		let biggest_win = |idx| {
+			records_broken(&tourny_results, idx)
+				.into_iter()
+				.find(|r| r.starts_with("Biggest win"))
+		};
+		assert_eq!(biggest_win(1), None);
+		assert_eq!(
+			biggest_win(2).as_deref(),
+			Some("Biggest win: Moai 6-0 Cursed, Test Cup (previously Cursed 5-0 Moai, Test Cup)")
+		);
+	}
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use tokio::fs;
+
+use crate::bracket::{Round, Stage};
+use crate::fixture::{Fixture, GoalType};
+use crate::players::PlayerIdentities;
+use crate::tournament::TournamentResult;
+use common::{
+	errors::{ConfigError, ToolError},
+	PlayerName, TeamName,
+};
+use lineupper::{player::Position, roster::Roster, slugify};
+
+// Lineupper rosters of the teams that played, keyed by team.
+pub struct TeamRosters {
+	rosters: HashMap<TeamName, Roster>,
+	// A readable line for each team without a roster file, for the caller to warn with.
+	missing: Vec<String>,
+}
+
+impl TeamRosters {
+	// Roster files are named like lineupper names them, e.g. "big-funky.toml". Teams without
+	// a roster file are skipped.
+	pub async fn load(
+		folder: &Path,
+		teams: impl Iterator<Item = TeamName>,
+	) -> Result<Self, ToolError> {
+		if !folder.is_dir() {
+			return Err(ConfigError::MissingRosterFolder(folder.display().to_string()).into());
+		}
+
+		let mut rosters = HashMap::new();
+		let mut missing = Vec::new();
+		for team in teams {
+			let roster_path = folder.join(slugify(&team.to_string()) + ".toml");
+			if !roster_path.is_file() {
+				missing.push(format!(
+					"No roster for '{team}' at '{}'.",
+					roster_path.display()
+				));
+				continue;
+			}
+
+			let roster_string = fs::read_to_string(&roster_path).await?;
+			let roster: Roster = toml::from_str(&roster_string).map_err(|e| {
+				ConfigError::InvalidRoster(roster_path.display().to_string(), e.to_string())
+			})?;
+			rosters.insert(team, roster);
+		}
+
+		Ok(Self { rosters, missing })
+	}
+
+	pub fn missing(&self) -> &[String] {
+		&self.missing
+	}
+
+	pub fn get(&self, team: TeamName) -> Option<&Roster> {
+		self.rosters.get(&team)
+	}
+
+	// The active goalkeepers on the team's roster, by the names the stats use. Empty if the
+	// team has no roster.
+	pub fn keepers(&self, team: TeamName, identities: &PlayerIdentities) -> Vec<PlayerName> {
+		let Some(roster) = self.get(team) else {
+			return Vec::new();
+		};
+		roster
+			.active
+			.iter()
+			.filter(|p| p.position == Position::GK)
+			.map(|p| identities.resolve(&p.name, team))
+			.collect()
+	}
+
+	// Every scorer and assister that isn't on the roster of the team they're credited for, as
+	// a readable line pointing at the fixture. Teams without a roster aren't checked.
+	pub fn violations(
+		&self,
+		tourny_results: &[TournamentResult],
+		identities: &PlayerIdentities,
+	) -> Vec<String> {
+		let mut violations = Vec::new();
+		for tr in tourny_results {
+			for round in &tr.rounds {
+				for fixture in &round.fixtures {
+					for (team, opponent, scorers, assisters, goals, keeper) in [
+						(
+							fixture.team1,
+							fixture.team2,
+							&fixture.scorers1,
+							&fixture.assisters1,
+							&fixture.goals1,
+							&fixture.keeper1,
+						),
+						(
+							fixture.team2,
+							fixture.team1,
+							&fixture.scorers2,
+							&fixture.assisters2,
+							&fixture.goals2,
+							&fixture.keeper2,
+						),
+					] {
+						// Own goals are scored by a player on the opponent's roster.
+						let own_goal_scorers = goals
+							.iter()
+							.flatten()
+							.filter(|g| g.goal_type == GoalType::OwnGoal)
+							.map(|g| (&g.scorer, opponent, "scored an own goal"));
+						let players = scorers
+							.iter()
+							.map(|p| (p, team, "scored"))
+							.chain(assisters.iter().map(|p| (p, team, "assisted")))
+							.chain(own_goal_scorers);
+
+						for (player, player_team, role) in players {
+							if self.is_on_roster(player, player_team, identities) != Some(false) {
+								continue;
+							}
+							let violation = format!(
+								"{}: '{player}' {role} for {player_team}, but isn't on its roster.",
+								describe_fixture(fixture, round, tr)
+							);
+							// Players scoring more than once are only reported once.
+							if !violations.contains(&violation) {
+								violations.push(violation);
+							}
+						}
+
+						if let Some(keeper) = keeper {
+							if self.is_keeper_on_roster(keeper, team, identities) == Some(false) {
+								violations.push(format!(
+									"{}: '{keeper}' kept goal for {team}, but isn't a goalkeeper on its roster.",
+									describe_fixture(fixture, round, tr)
+								));
+							}
+						}
+					}
+				}
+			}
+		}
+
+		violations
+	}
+
+	// None if the team has no roster.
+	fn is_on_roster(
+		&self,
+		player: &str,
+		team: TeamName,
+		identities: &PlayerIdentities,
+	) -> Option<bool> {
+		let roster = self.get(team)?;
+		Some(roster.active.iter().chain(&roster.reserve).any(|p| {
+			identities
+				.resolve(&p.name, team)
+				.eq_ignore_ascii_case(player)
+		}))
+	}
+
+	// None if the team has no roster.
+	fn is_keeper_on_roster(
+		&self,
+		player: &str,
+		team: TeamName,
+		identities: &PlayerIdentities,
+	) -> Option<bool> {
+		let roster = self.get(team)?;
+		Some(
+			roster
+				.active
+				.iter()
+				.chain(&roster.reserve)
+				.filter(|p| p.position == Position::GK)
+				.any(|p| {
+					identities
+						.resolve(&p.name, team)
+						.eq_ignore_ascii_case(player)
+				}),
+		)
+	}
+}
+
+// E.g. "Big Funny Cup 3 (WB SF): Cursed 2-1 Moai".
+fn describe_fixture(fixture: &Fixture, round: &Round, tourny_result: &TournamentResult) -> String {
+	// Group rounds are already named after the stage, e.g. "Group A".
+	let round_name = match round.stage {
+		Stage::Groups => round.name.clone(),
+		stage => format!("{} {}", stage.abbreviation(), round.name),
+	};
+	let mut description = format!(
+		"{} ({round_name}): {} {}-{} {}",
+		tourny_result.tournament_name, fixture.team1, fixture.score1, fixture.score2, fixture.team2
+	);
+	if let (Some(pen1), Some(pen2)) = (fixture.pen1, fixture.pen2) {
+		description += &format!(" ({pen1}-{pen2} p)");
+	}
+	description
+}
@@ -0,0 +1,412 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+use tokio::time;
+use toml::value::Datetime;
+
+use crate::bracket::Stage;
+use crate::cache::CupCache;
+use crate::entry::{compute_statistics, Statistics};
+use crate::fixture::Fixture;
+use crate::output::OutputFormat;
+use crate::team::team_slug;
+use crate::tournament::TournamentResult;
+use crate::watch::input_snapshot;
+use common::{
+	errors::{EntryError, ToolError},
+	watch::POLL_INTERVAL,
+	PlayerName, TeamName,
+};
+
+pub const DEFAULT_PORT: u16 = 8080;
+
+const ENDPOINTS: [&str; 8] = [
+	"/api/status",
+	"/api/tournaments?season=",
+	"/api/tournaments/{slug}",
+	"/api/teams",
+	"/api/teams/{slug}",
+	"/api/players?season=&tournament=&team=",
+	"/api/rankings?season=",
+	"/api/fixtures?season=&tournament=&team=&stage=",
+];
+
+#[derive(Default)]
+struct Served {
+	statistics: Option<Statistics>,
+	// Error of the last run, if it failed. The last good statistics are still served.
+	error: Option<String>,
+	runs: u32,
+}
+
+#[derive(Serialize)]
+struct Status<'a> {
+	ok: bool,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	error: Option<&'a str>,
+	runs: u32,
+	tournaments: usize,
+}
+
+#[derive(Serialize)]
+struct TournamentSummary<'a> {
+	name: &'a str,
+	slug: String,
+	season_num: u8,
+	#[serde(with = "crate::output::date")]
+	date: Datetime,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	winner: Option<TeamName>,
+	teams: usize,
+}
+
+#[derive(Serialize)]
+struct TeamSummary {
+	name: TeamName,
+	slug: String,
+	titles: u32,
+	tournaments_played: u32,
+	wins: u32,
+	draws: u32,
+	losses: u32,
+	goals_for: u32,
+	goals_against: u32,
+}
+
+#[derive(Serialize)]
+struct PlayerSummary<'a> {
+	player: &'a PlayerName,
+	team: TeamName,
+	goals: u32,
+	assists: u32,
+}
+
+#[derive(Serialize)]
+struct FixtureEntry<'a> {
+	tournament: &'a str,
+	season_num: u8,
+	#[serde(with = "crate::output::date")]
+	date: Datetime,
+	stage: Stage,
+	round: &'a str,
+	#[serde(flatten)]
+	fixture: &'a Fixture,
+}
+
+// Runs statter on the source folder in memory and serves the statistics as JSON on
+// localhost, running it again whenever one of its files changes. Only returns if the server
+// can't be started.
+pub async fn serve(source: PathBuf, port: u16) -> Result<(), ToolError> {
+	let server = Server::http(("127.0.0.1", port))
+		.map_err(|e| EntryError::ServerStartFailure(port, e.to_string()))?;
+	let served = Arc::new(RwLock::new(Served::default()));
+	{
+		let served = Arc::clone(&served);
+		std::thread::spawn(move || {
+			for request in server.incoming_requests() {
+				handle(&served, request);
+			}
+		});
+	}
+	println!(
+		"Serving the statistics of '{}' on http://127.0.0.1:{port}/api",
+		source.display()
+	);
+
+	let mut cache = Arc::new(CupCache::new());
+	let mut snapshot = None;
+	loop {
+		let new_snapshot = Some(input_snapshot(&source).await);
+		if new_snapshot != snapshot {
+			snapshot = new_snapshot;
+			let result = compute_statistics(source.clone(), Arc::clone(&cache)).await;
+			let mut served = served.write().unwrap_or_else(PoisonError::into_inner);
+			served.runs += 1;
+			match result {
+				Ok(mut statistics) => {
+					cache = Arc::new(std::mem::take(&mut statistics.cache));
+					println!(
+						"Loaded {} tournaments (run {}).",
+						statistics.tourny_results.len(),
+						served.runs
+					);
+					served.statistics = Some(statistics);
+					served.error = None;
+				}
+				Err(e) => {
+					eprintln!(
+						"ATTENTION: Run {} failed, serving the last statistics: {e}",
+						served.runs
+					);
+					served.error = Some(e.to_string());
+				}
+			}
+		}
+		time::sleep(POLL_INTERVAL).await;
+	}
+}
+
+fn handle(served: &RwLock<Served>, request: Request) {
+	let (status, body) = match request.method() {
+		Method::Get => {
+			let served = served.read().unwrap_or_else(PoisonError::into_inner);
+			route(&served, request.url())
+		}
+		_ => error(405, "Only GET requests are supported."),
+	};
+
+	let mut response = Response::from_string(body).with_status_code(status);
+	for (field, value) in [
+		("Content-Type", "application/json"),
+		// So overlays and pages opened from anywhere can fetch from it.
+		("Access-Control-Allow-Origin", "*"),
+	] {
+		if let Ok(header) = Header::from_bytes(field, value) {
+			response.add_header(header);
+		}
+	}
+	if let Err(e) = request.respond(response) {
+		eprintln!("ATTENTION: Failed to respond to a request: {e}");
+	}
+}
+
+fn route(served: &Served, url: &str) -> (u16, String) {
+	let (path, query) = url.split_once('?').unwrap_or((url, ""));
+	let query = &parse_query(query);
+	let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+	if let ["api", "status"] = segments[..] {
+		return json(&Status {
+			ok: served.error.is_none(),
+			error: served.error.as_deref(),
+			runs: served.runs,
+			tournaments: served
+				.statistics
+				.as_ref()
+				.map_or(0, |s| s.tourny_results.len()),
+		});
+	}
+	if let [] | ["api"] = segments[..] {
+		return json(&ENDPOINTS);
+	}
+	let Some(statistics) = &served.statistics else {
+		return error(
+			503,
+			served.error.as_deref().unwrap_or("Statter hasn't run yet."),
+		);
+	};
+
+	let season = match query.get("season").map(|s| s.parse::<u8>()) {
+		Some(Ok(season)) => Some(season),
+		Some(Err(_)) => return error(400, "Season has to be a number."),
+		None => None,
+	};
+	let tournaments = || {
+		statistics.tourny_results.iter().filter(move |tr| {
+			season.is_none_or(|s| tr.season_num == s)
+				&& query
+					.get("tournament")
+					.is_none_or(|t| matches(&tr.tournament_name, t))
+		})
+	};
+	let team_filter = |team: TeamName| {
+		query
+			.get("team")
+			.is_none_or(|t| matches(&team.to_string(), t))
+	};
+
+	match segments[..] {
+		["api", "tournaments"] => json(
+			&tournaments()
+				.map(|tr| TournamentSummary {
+					name: &tr.tournament_name,
+					slug: tr.slug(),
+					season_num: tr.season_num,
+					date: tr.date,
+					winner: tr
+						.team_placements
+						.iter()
+						.find(|tp| tp.placement == Some(1))
+						.map(|tp| tp.team.name),
+					teams: tr.team_placements.len(),
+				})
+				.collect::<Vec<_>>(),
+		),
+		["api", "tournaments", slug] => {
+			match statistics
+				.tourny_results
+				.iter()
+				.find(|tr| tr.slug() == slug)
+			{
+				Some(tr) => json(tr),
+				None => error(404, "No tournament has that slug."),
+			}
+		}
+		["api", "teams"] => json(
+			&statistics
+				.sorted_teams()
+				.into_iter()
+				.map(|team| {
+					let honours = team.trophy_cabinet.as_ref().map(|tc| &tc.all_time);
+					TeamSummary {
+						name: team.name,
+						slug: team_slug(team.name),
+						titles: honours.map_or(0, |h| h.titles),
+						tournaments_played: honours.map_or(0, |h| h.tournaments_played),
+						wins: team.wins,
+						draws: team.draws,
+						losses: team.losses,
+						goals_for: team.goals_for,
+						goals_against: team.goals_against,
+					}
+				})
+				.collect::<Vec<_>>(),
+		),
+		["api", "teams", slug] => {
+			match statistics
+				.teams
+				.values()
+				.find(|t| team_slug(t.name) == slug)
+			{
+				Some(team) => json(team),
+				None => error(404, "No team has that slug."),
+			}
+		}
+		["api", "players"] => json(&players(tournaments(), team_filter)),
+		["api", "rankings"] => json(
+			&statistics
+				.seasons
+				.seasons
+				.iter()
+				.filter(|s| season.is_none_or(|season| s.season_num == season))
+				.collect::<Vec<_>>(),
+		),
+		["api", "fixtures"] => {
+			let stage_filter = |stage: Stage| {
+				query.get("stage").is_none_or(|s| {
+					matches(&stage.to_string(), s) || matches(stage.abbreviation(), s)
+				})
+			};
+			let fixtures: Vec<FixtureEntry> = tournaments()
+				.flat_map(|tr| {
+					tr.rounds
+						.iter()
+						.filter(|round| stage_filter(round.stage))
+						.flat_map(move |round| {
+							round.fixtures.iter().map(move |fixture| FixtureEntry {
+								tournament: &tr.tournament_name,
+								season_num: tr.season_num,
+								date: tr.date,
+								stage: round.stage,
+								round: &round.name,
+								fixture,
+							})
+						})
+				})
+				.filter(|f| team_filter(f.fixture.team1) || team_filter(f.fixture.team2))
+				.collect();
+			json(&fixtures)
+		}
+		_ => error(404, "Unknown endpoint. See /api for the endpoints."),
+	}
+}
+
+// Goals and assists per player over the given tournaments, most goals first.
+fn players<'a>(
+	tourny_results: impl Iterator<Item = &'a TournamentResult>,
+	team_filter: impl Fn(TeamName) -> bool,
+) -> Vec<PlayerSummary<'a>> {
+	let mut players: HashMap<(&PlayerName, TeamName), (u32, u32)> = HashMap::new();
+	for tr in tourny_results {
+		for (player, goals, team) in &tr.scorers {
+			players.entry((player, *team)).or_default().0 += goals;
+		}
+		for (player, assists, team) in &tr.assisters {
+			players.entry((player, *team)).or_default().1 += assists;
+		}
+	}
+
+	let mut players: Vec<PlayerSummary> = players
+		.into_iter()
+		.filter(|((_, team), _)| team_filter(*team))
+		.map(|((player, team), (goals, assists))| PlayerSummary {
+			player,
+			team,
+			goals,
+			assists,
+		})
+		.collect();
+	players.sort_unstable_by(|a, b| {
+		b.goals
+			.cmp(&a.goals)
+			.then(b.assists.cmp(&a.assists))
+			.then(a.player.to_lowercase().cmp(&b.player.to_lowercase()))
+			.then(a.team.cmp(&b.team))
+	});
+	players
+}
+
+// Filters match regardless of case, spaces and punctuation, so both "The Chairs" and
+// "the-chairs" find The Chairs.
+fn matches(value: &str, filter: &str) -> bool {
+	let squash = |s: &str| -> String {
+		s.chars()
+			.filter(|c| c.is_alphanumeric())
+			.flat_map(char::to_lowercase)
+			.collect()
+	};
+	squash(value) == squash(filter)
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+	query
+		.split('&')
+		.filter_map(|pair| {
+			let (key, value) = pair.split_once('=')?;
+			let value = percent_decode(value);
+			(!value.is_empty()).then(|| (percent_decode(key), value))
+		})
+		.collect()
+}
+
+fn percent_decode(text: &str) -> String {
+	let mut bytes = Vec::new();
+	let mut chars = text.bytes();
+	while let Some(b) = chars.next() {
+		match b {
+			b'+' => bytes.push(b' '),
+			b'%' => {
+				let hex: Vec<u8> = chars.by_ref().take(2).collect();
+				match std::str::from_utf8(&hex)
+					.ok()
+					.and_then(|h| u8::from_str_radix(h, 16).ok())
+				{
+					Some(decoded) => bytes.push(decoded),
+					None => {
+						bytes.push(b'%');
+						bytes.extend(hex);
+					}
+				}
+			}
+			b => bytes.push(b),
+		}
+	}
+	String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn json<T: Serialize>(value: &T) -> (u16, String) {
+	match OutputFormat::Json.to_string(value) {
+		Ok(body) => (200, body),
+		Err(e) => error(500, &e.to_string()),
+	}
+}
+
+fn error(status: u16, message: &str) -> (u16, String) {
+	let body = OutputFormat::Json
+		.to_string(&HashMap::from([("error", message)]))
+		.unwrap_or_default();
+	(status, body)
+}
@@ -0,0 +1,716 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use tokio::fs;
+
+use crate::bracket::{ordinal, Stage};
+use crate::fixture::Fixture;
+use crate::honours::TitlesTable;
+use crate::rankings::Seasons;
+use crate::records::{FixtureRef, Records, TeamTournamentsRecord};
+use crate::svg::{bracket_svg, escape};
+use crate::team::{team_slug, Team};
+use crate::tournament::TournamentResult;
+use common::{errors::ToolError, PlayerName, TeamName};
+use lineupper::slugify;
+
+// Kept inline, so the site works offline and from any folder.
+const STYLE: &str =
+	"body{font-family:sans-serif;margin:0 auto;max-width:60em;padding:1em;color:#222}\
+nav a{margin-right:1em}\
+table{border-collapse:collapse;margin:0.5em 0 1.5em}\
+th,td{border:1px solid #ccc;padding:0.25em 0.6em;text-align:left}\
+th{background:#eee}\
+.muted{color:#777}";
+
+struct PlayerTournament {
+	tournament_name: String,
+	goals: u32,
+	assists: u32,
+}
+
+// Same-named players on different teams get a page each, like everywhere else in the stats.
+struct PlayerPage {
+	name: PlayerName,
+	team: TeamName,
+	slug: String,
+	goals: u32,
+	assists: u32,
+	tournaments: Vec<PlayerTournament>,
+}
+
+// Static HTML pages made from the same data as the TOML stats.
+pub struct Site<'a> {
+	tourny_results: &'a [TournamentResult],
+	teams: Vec<&'a Team>,
+	seasons: &'a Seasons,
+	records: &'a Records,
+	players: Vec<PlayerPage>,
+	player_slugs: HashMap<(PlayerName, TeamName), String>,
+	tournament_slugs: HashMap<String, String>,
+}
+
+impl<'a> Site<'a> {
+	pub fn from(
+		tourny_results: &'a [TournamentResult],
+		teams: impl Iterator<Item = &'a Team>,
+		seasons: &'a Seasons,
+		records: &'a Records,
+	) -> Self {
+		let mut teams: Vec<&Team> = teams.collect();
+		teams.sort_unstable_by_key(|t| t.name);
+
+		let mut players: Vec<PlayerPage> = Vec::new();
+		for tr in tourny_results {
+			let goals = tr.scorers.iter().map(|(p, g, t)| (p, *g, 0, *t));
+			let assists = tr.assisters.iter().map(|(p, a, t)| (p, 0, *a, *t));
+			for (name, goals, assists, team) in goals.chain(assists) {
+				let idx = match players
+					.iter()
+					.position(|p| &p.name == name && p.team == team)
+				{
+					Some(idx) => idx,
+					None => {
+						players.push(PlayerPage {
+							name: name.clone(),
+							team,
+							slug: String::new(),
+							goals: 0,
+							assists: 0,
+							tournaments: Vec::new(),
+						});
+						players.len() - 1
+					}
+				};
+				let player = &mut players[idx];
+				player.goals += goals;
+				player.assists += assists;
+				match player
+					.tournaments
+					.iter_mut()
+					.find(|pt| pt.tournament_name == tr.tournament_name)
+				{
+					Some(pt) => {
+						pt.goals += goals;
+						pt.assists += assists;
+					}
+					None => player.tournaments.push(PlayerTournament {
+						tournament_name: tr.tournament_name.clone(),
+						goals,
+						assists,
+					}),
+				}
+			}
+		}
+		players.sort_unstable_by(|a, b| {
+			b.goals
+				.cmp(&a.goals)
+				.then(b.assists.cmp(&a.assists))
+				.then(a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+				.then(a.team.cmp(&b.team))
+		});
+
+		// Different names can have the same slug, e.g. "Del Piero" and "del-piero".
+		let mut player_slugs: HashMap<(PlayerName, TeamName), String> = HashMap::new();
+		for player in &mut players {
+			let base = slugify(&format!("{} {}", player.name, player.team));
+			let mut slug = base.clone();
+			let mut n = 2;
+			while player_slugs.values().any(|s| *s == slug) {
+				slug = format!("{base}-{n}");
+				n += 1;
+			}
+			player.slug = slug.clone();
+			player_slugs.insert((player.name.clone(), player.team), slug);
+		}
+
+		let tournament_slugs = tourny_results
+			.iter()
+			.map(|tr| (tr.tournament_name.clone(), tr.slug()))
+			.collect();
+
+		Self {
+			tourny_results,
+			teams,
+			seasons,
+			records,
+			players,
+			player_slugs,
+			tournament_slugs,
+		}
+	}
+
+	pub async fn write(&self, folder: &Path) -> Result<(), ToolError> {
+		// Start over, so pages of renamed tournaments and players don't linger.
+		if folder.is_dir() {
+			fs::remove_dir_all(folder).await?;
+		}
+		for sub_folder in ["tournaments", "teams", "players"] {
+			fs::create_dir_all(folder.join(sub_folder)).await?;
+		}
+
+		fs::write(folder.join("index.html"), self.index_page()).await?;
+		fs::write(folder.join("rankings.html"), self.rankings_page()).await?;
+		fs::write(folder.join("records.html"), self.records_page()).await?;
+		for tr in self.tourny_results {
+			let path = folder.join("tournaments").join(tr.slug() + ".html");
+			fs::write(path, self.tournament_page(tr)).await?;
+		}
+		for team in &self.teams {
+			let path = folder.join("teams").join(team_slug(team.name) + ".html");
+			fs::write(path, self.team_page(team)).await?;
+		}
+		for player in &self.players {
+			let path = folder.join("players").join(player.slug.clone() + ".html");
+			fs::write(path, self.player_page(player)).await?;
+		}
+
+		Ok(())
+	}
+
+	fn index_page(&self) -> String {
+		let root = "";
+		let mut body = String::from("<h1>Big Funny Cup stats</h1>");
+
+		body += "<h2>Tournaments</h2>";
+		let rows = self
+			.tourny_results
+			.iter()
+			.rev()
+			.map(|tr| {
+				let winner = tr
+					.team_placements
+					.iter()
+					.find(|tp| tp.placement == Some(1))
+					.map_or(String::new(), |tp| self.team_link(root, tp.team.name));
+				vec![
+					tr.date.to_string(),
+					tr.season_num.to_string(),
+					self.tournament_link(root, &tr.tournament_name),
+					winner,
+				]
+			})
+			.collect();
+		body += &table(&["Date", "Season", "Tournament", "Winner"], rows);
+
+		body += "<h2>Titles</h2>";
+		let titles = TitlesTable::from(self.teams.iter().copied());
+		let rows = titles
+			.teams
+			.iter()
+			.map(|entry| {
+				vec![
+					entry.rank.to_string(),
+					self.team_link(root, entry.team),
+					entry.titles.to_string(),
+					entry.runner_ups.to_string(),
+					entry.podiums.to_string(),
+					entry.tournaments_played.to_string(),
+					entry.best_finish.map_or(String::new(), ordinal),
+				]
+			})
+			.collect();
+		body += &table(
+			&[
+				"#",
+				"Team",
+				"Titles",
+				"Runner-ups",
+				"Podiums",
+				"Played",
+				"Best finish",
+			],
+			rows,
+		);
+
+		body += "<h2>Players</h2>";
+		let rows = self
+			.players
+			.iter()
+			.map(|p| {
+				vec![
+					self.player_link(root, &p.name, p.team),
+					self.team_link(root, p.team),
+					p.goals.to_string(),
+					p.assists.to_string(),
+				]
+			})
+			.collect();
+		body += &table(&["Player", "Team", "Goals", "Assists"], rows);
+
+		page("Big Funny Cup stats", root, &body)
+	}
+
+	fn rankings_page(&self) -> String {
+		let root = "";
+		let mut body = String::from("<h1>Rankings</h1>");
+		for season in self.seasons.seasons.iter().rev() {
+			body += &format!("<h2>Season {}</h2>", season.season_num);
+			let tournaments: Vec<String> = season
+				.tournaments
+				.iter()
+				.map(|t| self.tournament_link(root, t))
+				.collect();
+			body += &format!("<p class=\"muted\">{}</p>", tournaments.join(", "));
+
+			let mut rankings: Vec<_> = season.rankings.iter().collect();
+			rankings.sort_by_key(|r| r.ranks.last().copied().unwrap_or(u8::MAX));
+			let rows = rankings
+				.iter()
+				.map(|r| {
+					vec![
+						r.ranks.last().map_or(String::new(), u8::to_string),
+						self.team_link(root, r.name),
+						r.ranking_points
+							.last()
+							.map_or(String::new(), u32::to_string),
+					]
+				})
+				.collect();
+			body += &table(&["#", "Team", "Points"], rows);
+		}
+
+		page("Rankings", root, &body)
+	}
+
+	fn records_page(&self) -> String {
+		let root = "";
+		let records = self.records;
+		let mut body = String::from("<h1>Records</h1>");
+
+		for (title, fixtures) in [
+			("Biggest wins", &records.biggest_wins),
+			(
+				"Highest scoring fixtures",
+				&records.highest_scoring_fixtures,
+			),
+			("Longest shoot-outs", &records.longest_shootouts),
+		] {
+			body += &format!("<h2>{title}</h2>");
+			let rows = fixtures
+				.iter()
+				.map(|f| self.fixture_ref_row(root, f))
+				.collect();
+			body += &table(&["Tournament", "Round", "Fixture"], rows);
+		}
+
+		body += "<h2>Most goals in a fixture</h2>";
+		let rows = records
+			.most_goals_in_fixture
+			.iter()
+			.map(|r| {
+				let mut row = vec![
+					self.player_link(root, &r.player, r.team),
+					self.team_link(root, r.team),
+					r.goals.to_string(),
+				];
+				row.extend(self.fixture_ref_row(root, &r.fixture));
+				row
+			})
+			.collect();
+		body += &table(
+			&["Player", "Team", "Goals", "Tournament", "Round", "Fixture"],
+			rows,
+		);
+
+		body += "<h2>Most goals in a tournament</h2>";
+		let rows = records
+			.most_goals_in_tournament
+			.iter()
+			.map(|r| {
+				vec![
+					self.player_link(root, &r.player, r.team),
+					self.team_link(root, r.team),
+					r.goals.to_string(),
+					self.tournament_link(root, &r.tournament_name),
+				]
+			})
+			.collect();
+		body += &table(&["Player", "Team", "Goals", "Tournament"], rows);
+
+		for (title, team_records) in [
+			("Most titles", &records.most_titles),
+			("Most consecutive finals", &records.most_consecutive_finals),
+		] {
+			body += &format!("<h2>{title}</h2>");
+			body += &self.team_tournaments_table(root, team_records);
+		}
+
+		page("Records", root, &body)
+	}
+
+	fn tournament_page(&self, tr: &TournamentResult) -> String {
+		let root = "../";
+		let mut body = format!(
+			"<h1>{}</h1><p class=\"muted\">Season {}, {}</p>",
+			escape(&tr.tournament_name),
+			tr.season_num,
+			tr.date
+		);
+
+		body += "<h2>Final standings</h2>";
+		let mut placements: Vec<_> = tr.team_placements.iter().collect();
+		placements.sort_by_key(|tp| tp.placement);
+		let rows = placements
+			.iter()
+			.map(|tp| {
+				let path = tr
+					.paths
+					.iter()
+					.find(|p| p.team == tp.team.name)
+					.map_or(String::new(), |p| escape(&p.path));
+				vec![
+					tp.placement.map_or(String::new(), ordinal),
+					self.team_link(root, tp.team.name),
+					format!("{}-{}-{}", tp.team.wins, tp.team.draws, tp.team.losses),
+					format!("{}-{}", tp.team.goals_for, tp.team.goals_against),
+					path,
+				]
+			})
+			.collect();
+		body += &table(&["#", "Team", "W-D-L", "Goals", "Path"], rows);
+
+		for group_table in tr.group_tables.iter().flatten() {
+			body += &format!("<h2>Group {}</h2>", group_table.group);
+			let rows = group_table
+				.standings
+				.iter()
+				.map(|s| {
+					vec![
+						s.position.to_string(),
+						self.team_link(root, s.team),
+						s.played.to_string(),
+						s.won.to_string(),
+						s.drawn.to_string(),
+						s.lost.to_string(),
+						format!("{}-{}", s.goals_for, s.goals_against),
+						s.goal_difference.to_string(),
+						s.points.to_string(),
+						s.status.to_string(),
+					]
+				})
+				.collect();
+			body += &table(
+				&["#", "Team", "P", "W", "D", "L", "Goals", "GD", "Pts", ""],
+				rows,
+			);
+		}
+
+		body += "<h2>Bracket</h2>";
+		body += &bracket_svg(tr);
+
+		body += "<h2>Fixtures</h2>";
+		for round in &tr.rounds {
+			let round_name = match round.stage {
+				Stage::Groups => round.name.clone(),
+				Stage::GrandFinal => Stage::GrandFinal.to_string(),
+				stage => format!("{stage} – {}", round.name),
+			};
+			body += &format!("<h3>{}</h3>", escape(&round_name));
+			let rows = round
+				.fixtures
+				.iter()
+				.map(|f| {
+					vec![
+						self.fixture_html(root, f),
+						self.player_links(root, &f.scorers1, f.team1),
+						self.player_links(root, &f.scorers2, f.team2),
+					]
+				})
+				.collect();
+			body += &table(&["Fixture", "Scorers (home)", "Scorers (away)"], rows);
+		}
+
+		for (title, players) in [
+			("Top scorers", &tr.scorers),
+			("Top assisters", &tr.assisters),
+		] {
+			body += &format!("<h2>{title}</h2>");
+			let rows = players
+				.iter()
+				.map(|(player, count, team)| {
+					vec![
+						self.player_link(root, player, *team),
+						self.team_link(root, *team),
+						count.to_string(),
+					]
+				})
+				.collect();
+			body += &table(&["Player", "Team", "Total"], rows);
+		}
+
+		page(&tr.tournament_name, root, &body)
+	}
+
+	fn team_page(&self, team: &Team) -> String {
+		let root = "../";
+		let mut body = format!("<h1>{}</h1>", escape(&team.name.to_string()));
+
+		body += &table(
+			&[
+				"Played",
+				"W-D-L",
+				"Goals",
+				"Clean sheets",
+				"Failed to score",
+				"Conceded per match",
+				"Form",
+			],
+			vec![vec![
+				team.matches_played().to_string(),
+				format!("{}-{}-{}", team.wins, team.draws, team.losses),
+				format!("{}-{}", team.goals_for, team.goals_against),
+				team.clean_sheets.to_string(),
+				team.failed_to_score.to_string(),
+				team.goals_against_per_match.to_string(),
+				team.streaks
+					.as_ref()
+					.map_or(String::new(), |s| s.form.clone()),
+			]],
+		);
+
+		if let Some(cabinet) = &team.trophy_cabinet {
+			body += "<h2>Trophy cabinet</h2>";
+			let honours = &cabinet.all_time;
+			body += &table(
+				&["Titles", "Runner-ups", "Podiums", "Played", "Best finish"],
+				vec![vec![
+					honours.titles.to_string(),
+					honours.runner_ups.to_string(),
+					honours.podiums.to_string(),
+					honours.tournaments_played.to_string(),
+					honours.best_finish.map_or(String::new(), ordinal),
+				]],
+			);
+		}
+
+		if let Some(seasons) = &team.seasons {
+			body += "<h2>Seasons</h2>";
+			let rows = seasons
+				.iter()
+				.map(|s| {
+					vec![
+						s.season_num.to_string(),
+						ordinal(s.rank),
+						s.ranking_points.to_string(),
+						format!("{}-{}-{}", s.wins, s.draws, s.losses),
+						format!("{}-{}", s.goals_for, s.goals_against),
+						s.clean_sheets.to_string(),
+					]
+				})
+				.collect();
+			body += &table(
+				&["Season", "Rank", "Points", "W-D-L", "Goals", "Clean sheets"],
+				rows,
+			);
+		}
+
+		if let Some(participations) = &team.participations {
+			body += "<h2>Tournaments</h2>";
+			let rows = participations
+				.iter()
+				.rev()
+				.map(|p| {
+					vec![
+						p.date.to_string(),
+						self.tournament_link(root, &p.tournament_name),
+						ordinal(p.placement),
+					]
+				})
+				.collect();
+			body += &table(&["Date", "Tournament", "Placement"], rows);
+		}
+
+		for (title, players) in [("Scorers", &team.scorers), ("Assisters", &team.assisters)] {
+			body += &format!("<h2>{title}</h2>");
+			let rows = players
+				.iter()
+				.map(|(player, count)| {
+					vec![self.player_link(root, player, team.name), count.to_string()]
+				})
+				.collect();
+			body += &table(&["Player", "Total"], rows);
+		}
+
+		if let Some(matchups) = &team.matchups {
+			body += "<h2>Matchups</h2>";
+			let rows = matchups
+				.iter()
+				.map(|m| {
+					let last = m.last_meeting.as_ref().map_or(String::new(), |lm| {
+						format!(
+							"{} {}-{} ({})",
+							self.tournament_link(root, &lm.tournament_name),
+							lm.goals_for,
+							lm.goals_against,
+							lm.result
+						)
+					});
+					vec![
+						self.team_link(root, m.opponent_name),
+						format!("{}-{}-{}", m.wins, m.draws, m.losses),
+						format!("{}-{}", m.goals_for, m.goals_against),
+						last,
+					]
+				})
+				.collect();
+			body += &table(&["Opponent", "W-D-L", "Goals", "Last meeting"], rows);
+		}
+
+		page(&team.name.to_string(), root, &body)
+	}
+
+	fn player_page(&self, player: &PlayerPage) -> String {
+		let root = "../";
+		let mut body = format!(
+			"<h1>{}</h1><p>{}</p>",
+			escape(&player.name),
+			self.team_link(root, player.team)
+		);
+		body += &table(
+			&["Goals", "Assists"],
+			vec![vec![player.goals.to_string(), player.assists.to_string()]],
+		);
+
+		body += "<h2>Tournaments</h2>";
+		let rows = player
+			.tournaments
+			.iter()
+			.rev()
+			.map(|pt| {
+				vec![
+					self.tournament_link(root, &pt.tournament_name),
+					pt.goals.to_string(),
+					pt.assists.to_string(),
+				]
+			})
+			.collect();
+		body += &table(&["Tournament", "Goals", "Assists"], rows);
+
+		page(&player.name, root, &body)
+	}
+
+	fn team_tournaments_table(&self, root: &str, team_records: &[TeamTournamentsRecord]) -> String {
+		let rows = team_records
+			.iter()
+			.map(|r| {
+				let tournaments: Vec<String> = r
+					.tournaments
+					.iter()
+					.map(|t| self.tournament_link(root, t))
+					.collect();
+				vec![
+					self.team_link(root, r.team),
+					r.count.to_string(),
+					tournaments.join(", "),
+				]
+			})
+			.collect();
+		table(&["Team", "Count", "Tournaments"], rows)
+	}
+
+	fn fixture_ref_row(&self, root: &str, f: &FixtureRef) -> Vec<String> {
+		let mut fixture = format!(
+			"{} {}-{} {}",
+			self.team_link(root, f.team1),
+			f.score1,
+			f.score2,
+			self.team_link(root, f.team2)
+		);
+		if let (Some(pen1), Some(pen2)) = (f.pen1, f.pen2) {
+			fixture += &format!(" ({pen1}-{pen2} p)");
+		}
+		vec![
+			self.tournament_link(root, &f.tournament_name),
+			escape(&format!("{} {}", f.stage.abbreviation(), f.round)),
+			fixture,
+		]
+	}
+
+	fn fixture_html(&self, root: &str, f: &Fixture) -> String {
+		let winner = f.winner().ok().flatten();
+		let team = |name: TeamName| match winner == Some(name) {
+			true => format!("<b>{}</b>", self.team_link(root, name)),
+			false => self.team_link(root, name),
+		};
+		let mut fixture = format!(
+			"{} {}-{} {}",
+			team(f.team1),
+			f.score1,
+			f.score2,
+			team(f.team2)
+		);
+		if let (Some(pen1), Some(pen2)) = (f.pen1, f.pen2) {
+			fixture += &format!(" ({pen1}-{pen2} p)");
+		}
+		fixture
+	}
+
+	fn team_link(&self, root: &str, team: TeamName) -> String {
+		format!(
+			"<a href=\"{root}teams/{}.html\">{}</a>",
+			team_slug(team),
+			escape(&team.to_string())
+		)
+	}
+
+	// Players without a page, e.g. goalkeepers who never scored or assisted, aren't linked.
+	fn player_link(&self, root: &str, player: &str, team: TeamName) -> String {
+		match self.player_slugs.get(&(player.to_string(), team)) {
+			Some(slug) => format!(
+				"<a href=\"{root}players/{slug}.html\">{}</a>",
+				escape(player)
+			),
+			None => escape(player),
+		}
+	}
+
+	fn player_links(&self, root: &str, players: &[PlayerName], team: TeamName) -> String {
+		let links: Vec<String> = players
+			.iter()
+			.map(|p| self.player_link(root, p, team))
+			.collect();
+		links.join(", ")
+	}
+
+	fn tournament_link(&self, root: &str, tournament_name: &str) -> String {
+		match self.tournament_slugs.get(tournament_name) {
+			Some(slug) => format!(
+				"<a href=\"{root}tournaments/{slug}.html\">{}</a>",
+				escape(tournament_name)
+			),
+			None => escape(tournament_name),
+		}
+	}
+}
+
+fn page(title: &str, root: &str, body: &str) -> String {
+	format!(
+		"<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+		<title>{}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+		<nav><a href=\"{root}index.html\">Home</a><a href=\"{root}rankings.html\">Rankings</a>\
+		<a href=\"{root}records.html\">Records</a></nav>\n{body}\n</body>\n</html>\n",
+		escape(title)
+	)
+}
+
+// Cells are expected to be HTML already.
+fn table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+	if rows.is_empty() {
+		return String::from("<p class=\"muted\">None yet.</p>");
+	}
+
+	let mut html = String::from("<table>\n<tr>");
+	for header in headers {
+		html += &format!("<th>{}</th>", escape(header));
+	}
+	html += "</tr>\n";
+	for row in rows {
+		html += "<tr>";
+		for cell in row {
+			html += &format!("<td>{cell}</td>");
+		}
+		html += "</tr>\n";
+	}
+	html + "</table>\n"
+}
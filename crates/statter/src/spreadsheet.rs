@@ -0,0 +1,349 @@
+use std::path::Path;
+
+use csv::{Writer, WriterBuilder};
+use serde::Serialize;
+use tokio::fs;
+
+use crate::rankings::Seasons;
+use crate::team::Team;
+use crate::tournament::TournamentResult;
+use common::{
+	errors::{OutputError, ToolError},
+	PlayerName, TeamName,
+};
+
+// Headers are written by hand, so they're there even when a table is empty. Keep them in the
+// order of the row's fields.
+const TEAMS_HEADER: &[&str] = &[
+	"team",
+	"tournaments_played",
+	"titles",
+	"runner_ups",
+	"podiums",
+	"matches_played",
+	"wins",
+	"draws",
+	"losses",
+	"goals_for",
+	"goals_against",
+	"goal_difference",
+	"clean_sheets",
+	"failed_to_score",
+	"penalties_played",
+	"penalties_goals_for",
+	"penalties_goals_against",
+];
+
+#[derive(Serialize)]
+struct TeamRow {
+	team: TeamName,
+	tournaments_played: u32,
+	titles: u32,
+	runner_ups: u32,
+	podiums: u32,
+	matches_played: u32,
+	wins: u32,
+	draws: u32,
+	losses: u32,
+	goals_for: u32,
+	goals_against: u32,
+	goal_difference: i64,
+	clean_sheets: u32,
+	failed_to_score: u32,
+	penalties_played: u32,
+	penalties_goals_for: u32,
+	penalties_goals_against: u32,
+}
+
+const RANKING_POINTS_HEADER: &[&str] = &["season", "tournament", "date", "team", "points"];
+
+// A team's ranking points from one tournament.
+#[derive(Serialize)]
+struct RankingPointsRow<'a> {
+	season: u8,
+	tournament: &'a str,
+	date: String,
+	team: TeamName,
+	points: u32,
+}
+
+const FIXTURES_HEADER: &[&str] = &[
+	"tournament",
+	"season",
+	"date",
+	"stage",
+	"round",
+	"team1",
+	"team2",
+	"score1",
+	"score2",
+	"pen1",
+	"pen2",
+	"winner",
+];
+
+#[derive(Serialize)]
+struct FixtureRow<'a> {
+	tournament: &'a str,
+	season: u8,
+	date: String,
+	stage: String,
+	round: &'a str,
+	team1: TeamName,
+	team2: TeamName,
+	score1: u8,
+	score2: u8,
+	pen1: Option<u8>,
+	pen2: Option<u8>,
+	winner: Option<TeamName>,
+}
+
+// Writes flat tables for spreadsheets: one per season's rankings, plus the ranking points of
+// every tournament, team totals, the scorer and assister leaderboards, and every fixture.
+// Only the season tables have a column per tournament, which new cups add at the end.
+pub async fn write_spreadsheets<'a>(
+	tourny_results: &[TournamentResult],
+	teams: impl Iterator<Item = &'a Team>,
+	seasons: &Seasons,
+	folder: &Path,
+) -> Result<(), ToolError> {
+	if !folder.is_dir() {
+		fs::create_dir(folder).await?;
+	}
+
+	for season in &seasons.seasons {
+		let path = folder.join(format!("season-{}-rankings.csv", season.season_num));
+		fs::write(
+			path,
+			season_rankings_csv(tourny_results, season.season_num, seasons)?,
+		)
+		.await?;
+	}
+
+	let mut writer = csv_writer(RANKING_POINTS_HEADER)?;
+	for tr in tourny_results {
+		for ranked_team in tr.get_teams_ranked() {
+			let Some(&points) = ranked_team.ranking_points.last() else {
+				continue;
+			};
+			writer
+				.serialize(RankingPointsRow {
+					season: tr.season_num,
+					tournament: &tr.tournament_name,
+					date: tr.date.to_string(),
+					team: ranked_team.name,
+					points,
+				})
+				.map_err(csv_error)?;
+		}
+	}
+	fs::write(folder.join("ranking-points.csv"), finish(writer)?).await?;
+
+	let mut teams: Vec<&Team> = teams.collect();
+	teams.sort_unstable_by_key(|t| t.name);
+
+	let mut writer = csv_writer(TEAMS_HEADER)?;
+	for team in &teams {
+		let honours = team
+			.trophy_cabinet
+			.as_ref()
+			.map(|tc| &tc.all_time)
+			.cloned()
+			.unwrap_or_default();
+		writer
+			.serialize(TeamRow {
+				team: team.name,
+				tournaments_played: honours.tournaments_played,
+				titles: honours.titles,
+				runner_ups: honours.runner_ups,
+				podiums: honours.podiums,
+				matches_played: team.matches_played(),
+				wins: team.wins,
+				draws: team.draws,
+				losses: team.losses,
+				goals_for: team.goals_for,
+				goals_against: team.goals_against,
+				goal_difference: team.goals_for as i64 - team.goals_against as i64,
+				clean_sheets: team.clean_sheets,
+				failed_to_score: team.failed_to_score,
+				penalties_played: team.penalties_played,
+				penalties_goals_for: team.penalties_goals_for,
+				penalties_goals_against: team.penalties_goals_against,
+			})
+			.map_err(csv_error)?;
+	}
+	fs::write(folder.join("teams.csv"), finish(writer)?).await?;
+
+	let scorers = teams
+		.iter()
+		.flat_map(|t| t.scorers.iter().map(|(name, goals)| (name, t.name, *goals)));
+	fs::write(
+		folder.join("scorers.csv"),
+		leaderboard_csv(scorers, "goals")?,
+	)
+	.await?;
+	let assisters = teams.iter().flat_map(|t| {
+		t.assisters
+			.iter()
+			.map(|(name, assists)| (name, t.name, *assists))
+	});
+	fs::write(
+		folder.join("assisters.csv"),
+		leaderboard_csv(assisters, "assists")?,
+	)
+	.await?;
+
+	let mut writer = csv_writer(FIXTURES_HEADER)?;
+	for tr in tourny_results {
+		for round in &tr.rounds {
+			for fixture in &round.fixtures {
+				writer
+					.serialize(FixtureRow {
+						tournament: &tr.tournament_name,
+						season: tr.season_num,
+						date: tr.date.to_string(),
+						stage: round.stage.to_string(),
+						round: &round.name,
+						team1: fixture.team1,
+						team2: fixture.team2,
+						score1: fixture.score1,
+						score2: fixture.score2,
+						pen1: fixture.pen1,
+						pen2: fixture.pen2,
+						winner: fixture.winner()?,
+					})
+					.map_err(csv_error)?;
+			}
+		}
+	}
+	fs::write(folder.join("fixtures.csv"), finish(writer)?).await?;
+
+	Ok(())
+}
+
+// Final standings of the season, then the points each team earned in every tournament of the
+// season and its season rank after it. Teams that didn't play a tournament have empty cells,
+// as do teams that weren't ranked yet.
+fn season_rankings_csv(
+	tourny_results: &[TournamentResult],
+	season_num: u8,
+	seasons: &Seasons,
+) -> Result<Vec<u8>, ToolError> {
+	let season_results: Vec<&TournamentResult> = tourny_results
+		.iter()
+		.filter(|tr| tr.season_num == season_num)
+		.collect();
+
+	let mut header = vec!["rank".to_string(), "team".to_string(), "points".to_string()];
+	for tr in &season_results {
+		header.push(format!("{} points", tr.tournament_name));
+		header.push(format!("{} rank", tr.tournament_name));
+	}
+	let mut writer = csv_writer(&header)?;
+
+	let season = seasons.seasons.iter().find(|s| s.season_num == season_num);
+	for (i, ranked_team) in season.into_iter().flat_map(|s| &s.rankings).enumerate() {
+		let points = ranked_team.ranking_points.last().copied().unwrap_or(0);
+		let mut record = vec![
+			(i + 1).to_string(),
+			ranked_team.name.to_string(),
+			points.to_string(),
+		];
+		// Ranks line up with the season's tournaments, with 0 before the team's first one.
+		for (position, tr) in season_results.iter().enumerate() {
+			let points = tr
+				.get_teams_ranked()
+				.into_iter()
+				.find(|rt| rt.name == ranked_team.name)
+				.and_then(|rt| rt.ranking_points.last().copied());
+			let rank = ranked_team
+				.ranks
+				.get(position)
+				.copied()
+				.filter(|&rank| rank > 0);
+			record.push(points.map_or(String::new(), |p| p.to_string()));
+			record.push(rank.map_or(String::new(), |r| r.to_string()));
+		}
+		writer.write_record(&record).map_err(csv_error)?;
+	}
+
+	finish(writer)
+}
+
+// Most first, then by name. Players with the same count share a rank.
+fn leaderboard_csv<'a>(
+	players: impl Iterator<Item = (&'a PlayerName, TeamName, u32)>,
+	count_header: &str,
+) -> Result<Vec<u8>, ToolError> {
+	let mut players: Vec<_> = players.collect();
+	players.sort_unstable_by(|(a_name, a_team, a_count), (b_name, b_team, b_count)| {
+		b_count
+			.cmp(a_count)
+			.then(a_name.to_lowercase().cmp(&b_name.to_lowercase()))
+			.then(a_team.cmp(b_team))
+	});
+
+	let mut writer = csv_writer(&["rank", "player", "team", count_header])?;
+	let mut rank = 0;
+	for (i, &(player, team, count)) in players.iter().enumerate() {
+		if i == 0 || players[i - 1].2 != count {
+			rank = i + 1;
+		}
+		writer
+			.write_record([
+				rank.to_string(),
+				player.clone(),
+				team.to_string(),
+				count.to_string(),
+			])
+			.map_err(csv_error)?;
+	}
+
+	finish(writer)
+}
+
+// A writer with the header row already written. Rows are serialized without headers of their
+// own.
+fn csv_writer(header: &[impl AsRef<[u8]>]) -> Result<Writer<Vec<u8>>, ToolError> {
+	let mut writer = WriterBuilder::new()
+		.has_headers(false)
+		.from_writer(Vec::new());
+	writer.write_record(header).map_err(csv_error)?;
+	Ok(writer)
+}
+
+fn finish(writer: Writer<Vec<u8>>) -> Result<Vec<u8>, ToolError> {
+	Ok(writer.into_inner().map_err(|e| e.into_error())?)
+}
+
+fn csv_error(e: csv::Error) -> ToolError {
+	OutputError::CsvFailure(e.to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_support::{block_on, TempDir};
+
+	#[test]
+	fn empty_tables_keep_their_headers() {
+		let folder = TempDir::new();
+		let csv_folder = folder.path().join("csv");
+		block_on(write_spreadsheets(
+			&[],
+			std::iter::empty(),
+			&Seasons::from(&[]),
+			&csv_folder,
+		))
+		.unwrap();
+
+		for (file, header) in [
+			("teams.csv", TEAMS_HEADER),
+			("ranking-points.csv", RANKING_POINTS_HEADER),
+			("fixtures.csv", FIXTURES_HEADER),
+		] {
+			let csv = std::fs::read_to_string(csv_folder.join(file)).unwrap();
+			assert_eq!(csv, header.join(",") + "\n", "{file}");
+		}
+	}
+}
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+
+use crate::fixture::Fixture;
+use crate::rankings::Seasons;
+use crate::team::Team;
+use crate::tournament::TournamentResult;
+use common::{
+	errors::{OutputError, ToolError},
+	TeamName,
+};
+
+const SCHEMA: &str = "
+CREATE TABLE teams (
+	id INTEGER PRIMARY KEY,
+	name TEXT NOT NULL UNIQUE,
+	wins INTEGER NOT NULL,
+	draws INTEGER NOT NULL,
+	losses INTEGER NOT NULL,
+	goals_for INTEGER NOT NULL,
+	goals_against INTEGER NOT NULL,
+	clean_sheets INTEGER NOT NULL,
+	failed_to_score INTEGER NOT NULL,
+	penalties_played INTEGER NOT NULL,
+	penalties_goals_for INTEGER NOT NULL,
+	penalties_goals_against INTEGER NOT NULL
+);
+CREATE TABLE tournaments (
+	id INTEGER PRIMARY KEY,
+	name TEXT NOT NULL,
+	slug TEXT NOT NULL,
+	season_num INTEGER NOT NULL,
+	date TEXT NOT NULL
+);
+CREATE TABLE participations (
+	tournament_id INTEGER NOT NULL REFERENCES tournaments(id),
+	team_id INTEGER NOT NULL REFERENCES teams(id),
+	placement INTEGER NOT NULL,
+	PRIMARY KEY (tournament_id, team_id)
+);
+CREATE TABLE fixtures (
+	id INTEGER PRIMARY KEY,
+	tournament_id INTEGER NOT NULL REFERENCES tournaments(id),
+	stage TEXT NOT NULL,
+	round TEXT NOT NULL,
+	team1_id INTEGER NOT NULL REFERENCES teams(id),
+	team2_id INTEGER NOT NULL REFERENCES teams(id),
+	score1 INTEGER NOT NULL,
+	score2 INTEGER NOT NULL,
+	pen1 INTEGER,
+	pen2 INTEGER,
+	winner_id INTEGER REFERENCES teams(id)
+);
+CREATE TABLE goals (
+	id INTEGER PRIMARY KEY,
+	fixture_id INTEGER NOT NULL REFERENCES fixtures(id),
+	team_id INTEGER NOT NULL REFERENCES teams(id),
+	scorer TEXT NOT NULL,
+	assister TEXT,
+	minute INTEGER,
+	type TEXT
+);
+CREATE TABLE assists (
+	id INTEGER PRIMARY KEY,
+	fixture_id INTEGER NOT NULL REFERENCES fixtures(id),
+	team_id INTEGER NOT NULL REFERENCES teams(id),
+	assister TEXT NOT NULL
+);
+CREATE TABLE rankings (
+	season_num INTEGER NOT NULL,
+	tournament_id INTEGER NOT NULL REFERENCES tournaments(id),
+	team_id INTEGER NOT NULL REFERENCES teams(id),
+	points INTEGER,
+	rank INTEGER NOT NULL,
+	PRIMARY KEY (tournament_id, team_id)
+);
+";
+
+// Writes every statistic of the run to one SQLite database, replacing the old one.
+//
+// Goals are listed under the team credited with them, so own goals are under the team that
+// didn't score them. Goals of fixtures without goal events have no assister, minute or type.
+// Their assists are still in the assists table, which has every assist of both formats.
+//
+// Rankings have a row per team for every tournament of the season from the team's first one
+// on. Points are those earned in that tournament, or NULL if the team didn't play in it.
+//
+// Tournament names don't have to be unique, so tournaments are only told apart by id.
+//
+// Blocks on the database, so it's meant to be run on a blocking task.
+pub fn write_database<'a>(
+	tourny_results: &[TournamentResult],
+	teams: impl Iterator<Item = &'a Team>,
+	seasons: &Seasons,
+	path: &Path,
+) -> Result<(), ToolError> {
+	if path.exists() {
+		std::fs::remove_file(path)?;
+	}
+	let mut conn = Connection::open(path).map_err(database_error)?;
+	conn.execute_batch(SCHEMA).map_err(database_error)?;
+	let tx = conn.transaction().map_err(database_error)?;
+
+	let mut teams: Vec<&Team> = teams.collect();
+	teams.sort_unstable_by_key(|t| t.name);
+	let mut team_ids: HashMap<TeamName, i64> = HashMap::new();
+	for team in teams {
+		tx.execute(
+			"INSERT INTO teams (name, wins, draws, losses, goals_for, goals_against, \
+			clean_sheets, failed_to_score, penalties_played, penalties_goals_for, \
+			penalties_goals_against) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+			params![
+				team.name.to_string(),
+				team.wins,
+				team.draws,
+				team.losses,
+				team.goals_for,
+				team.goals_against,
+				team.clean_sheets,
+				team.failed_to_score,
+				team.penalties_played,
+				team.penalties_goals_for,
+				team.penalties_goals_against,
+			],
+		)
+		.map_err(database_error)?;
+		team_ids.insert(team.name, tx.last_insert_rowid());
+	}
+
+	// In the same order as tourny_results.
+	let mut tournament_ids: Vec<i64> = Vec::new();
+	for tr in tourny_results {
+		tx.execute(
+			"INSERT INTO tournaments (name, slug, season_num, date) VALUES (?1, ?2, ?3, ?4)",
+			params![
+				tr.tournament_name,
+				tr.slug(),
+				tr.season_num,
+				tr.date.to_string()
+			],
+		)
+		.map_err(database_error)?;
+		let tournament_id = tx.last_insert_rowid();
+		tournament_ids.push(tournament_id);
+
+		for tp in &tr.team_placements {
+			tx.execute(
+				"INSERT INTO participations (tournament_id, team_id, placement) \
+				VALUES (?1, ?2, ?3)",
+				params![tournament_id, team_ids[&tp.team.name], tp.placement],
+			)
+			.map_err(database_error)?;
+		}
+
+		for round in &tr.rounds {
+			for fixture in &round.fixtures {
+				let winner = fixture.winner()?.map(|t| team_ids[&t]);
+				tx.execute(
+					"INSERT INTO fixtures (tournament_id, stage, round, team1_id, team2_id, \
+					score1, score2, pen1, pen2, winner_id) \
+					VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+					params![
+						tournament_id,
+						round.stage.to_string(),
+						round.name,
+						team_ids[&fixture.team1],
+						team_ids[&fixture.team2],
+						fixture.score1,
+						fixture.score2,
+						fixture.pen1,
+						fixture.pen2,
+						winner,
+					],
+				)
+				.map_err(database_error)?;
+				insert_goals(&tx, fixture, tx.last_insert_rowid(), &team_ids)?;
+			}
+		}
+	}
+
+	for season in &seasons.seasons {
+		for ranked_team in &season.rankings {
+			let season_results = tourny_results
+				.iter()
+				.zip(&tournament_ids)
+				.filter(|(tr, _)| tr.season_num == season.season_num);
+			for ((tr, &tournament_id), &rank) in season_results.zip(&ranked_team.ranks) {
+				// Not ranked yet.
+				if rank == 0 {
+					continue;
+				}
+				let points = tr
+					.get_teams_ranked()
+					.into_iter()
+					.find(|rt| rt.name == ranked_team.name)
+					.and_then(|rt| rt.ranking_points.last().copied());
+				tx.execute(
+					"INSERT INTO rankings (season_num, tournament_id, team_id, points, rank) \
+					VALUES (?1, ?2, ?3, ?4, ?5)",
+					params![
+						season.season_num,
+						tournament_id,
+						team_ids[&ranked_team.name],
+						points,
+						rank,
+					],
+				)
+				.map_err(database_error)?;
+			}
+		}
+	}
+
+	tx.commit().map_err(database_error)?;
+	Ok(())
+}
+
+fn insert_goals(
+	tx: &rusqlite::Transaction,
+	fixture: &Fixture,
+	fixture_id: i64,
+	team_ids: &HashMap<TeamName, i64>,
+) -> Result<(), ToolError> {
+	let sides = [
+		(fixture.team1, &fixture.scorers1, &fixture.assisters1, true),
+		(fixture.team2, &fixture.scorers2, &fixture.assisters2, false),
+	];
+	for (team, scorers, assisters, is_team1) in sides {
+		let team_id = team_ids[&team];
+		match fixture.goals(is_team1) {
+			Some(goals) => {
+				for goal in goals {
+					tx.execute(
+						"INSERT INTO goals (fixture_id, team_id, scorer, assister, minute, type) \
+						VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+						params![
+							fixture_id,
+							team_id,
+							goal.scorer,
+							goal.assister,
+							goal.minute,
+							goal.goal_type.to_string(),
+						],
+					)
+					.map_err(database_error)?;
+				}
+			}
+			None => {
+				for scorer in scorers {
+					tx.execute(
+						"INSERT INTO goals (fixture_id, team_id, scorer) VALUES (?1, ?2, ?3)",
+						params![fixture_id, team_id, scorer],
+					)
+					.map_err(database_error)?;
+				}
+			}
+		}
+		for assister in assisters {
+			tx.execute(
+				"INSERT INTO assists (fixture_id, team_id, assister) VALUES (?1, ?2, ?3)",
+				params![fixture_id, team_id, assister],
+			)
+			.map_err(database_error)?;
+		}
+	}
+
+	Ok(())
+}
+
+fn database_error(e: rusqlite::Error) -> ToolError {
+	OutputError::DatabaseFailure(e.to_string()).into()
+}
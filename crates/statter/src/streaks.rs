@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tournament::TournamentResult;
+use common::TeamName;
+
+const FORM_LENGTH: usize = 10;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct Streak {
+	pub current: u32,
+	pub longest: u32,
+}
+
+impl Streak {
+	fn update(&mut self, continues: bool) {
+		if continues {
+			self.current += 1;
+			self.longest = self.longest.max(self.current);
+		} else {
+			self.current = 0;
+		}
+	}
+}
+
+// Streaks over every fixture a team has played, in date order. Shoot-out results count
+// the same way they do for wins and losses.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Streaks {
+	pub unbeaten: Streak,
+	pub winning: Streak,
+	pub losing: Streak,
+	pub scoring: Streak,
+	// Last 10 results, oldest first.
+	pub form: String,
+}
+
+impl Streaks {
+	// NOTE: Expects the tournament results to be sorted by date.
+	pub fn all_from(tourny_results: &[TournamentResult]) -> HashMap<TeamName, Streaks> {
+		let mut teams_streaks: HashMap<TeamName, Streaks> = HashMap::new();
+		for tr in tourny_results {
+			for fixture in tr.rounds.iter().flat_map(|r| &r.fixtures) {
+				let winner = fixture.winner().ok().flatten();
+				for (team, goals_for) in [
+					(fixture.team1, fixture.score1),
+					(fixture.team2, fixture.score2),
+				] {
+					let result = match winner {
+						Some(w) if w == team => 'W',
+						Some(_) => 'L',
+						None => 'D',
+					};
+					teams_streaks
+						.entry(team)
+						.or_default()
+						.push(result, goals_for);
+				}
+			}
+		}
+
+		teams_streaks
+	}
+
+	fn push(&mut self, result: char, goals_for: u8) {
+		self.unbeaten.update(result != 'L');
+		self.winning.update(result == 'W');
+		self.losing.update(result == 'L');
+		self.scoring.update(goals_for > 0);
+
+		self.form.push(result);
+		if self.form.len() > FORM_LENGTH {
+			self.form.remove(0);
+		}
+	}
+}
+
+#[derive(Serialize)]
+pub struct StreakEntry {
+	pub team: TeamName,
+	pub current: u32,
+	pub longest: u32,
+}
+
+#[derive(Serialize)]
+pub struct TeamForm {
+	pub team: TeamName,
+	pub form: String,
+}
+
+// League-wide streaks, each table ranked by longest streak.
+#[derive(Serialize)]
+pub struct LeagueStreaks {
+	pub unbeaten: Vec<StreakEntry>,
+	pub winning: Vec<StreakEntry>,
+	pub losing: Vec<StreakEntry>,
+	pub scoring: Vec<StreakEntry>,
+	pub form: Vec<TeamForm>,
+}
+
+impl LeagueStreaks {
+	pub fn from(teams_streaks: &HashMap<TeamName, Streaks>) -> Self {
+		let table = |streak: fn(&Streaks) -> Streak| {
+			let mut entries: Vec<StreakEntry> = teams_streaks
+				.iter()
+				.map(|(team, streaks)| StreakEntry {
+					team: *team,
+					current: streak(streaks).current,
+					longest: streak(streaks).longest,
+				})
+				.collect();
+			entries.sort_unstable_by(|a, b| {
+				b.longest
+					.cmp(&a.longest)
+					.then(b.current.cmp(&a.current))
+					.then(a.team.cmp(&b.team))
+			});
+			entries
+		};
+
+		let mut form: Vec<TeamForm> = teams_streaks
+			.iter()
+			.map(|(team, streaks)| TeamForm {
+				team: *team,
+				form: streaks.form.clone(),
+			})
+			.collect();
+		form.sort_unstable_by_key(|f| f.team);
+
+		Self {
+			unbeaten: table(|s| s.unbeaten),
+			winning: table(|s| s.winning),
+			losing: table(|s| s.losing),
+			scoring: table(|s| s.scoring),
+			form,
+		}
+	}
+}
@@ -0,0 +1,233 @@
+use crate::bracket::{Round, Stage};
+use crate::fixture::Fixture;
+use crate::tournament::{GroupTable, Qualification, TournamentResult};
+
+const MARGIN: u32 = 20;
+const TITLE_HEIGHT: u32 = 40;
+const HEADING_HEIGHT: u32 = 24;
+const FIXTURE_WIDTH: u32 = 200;
+const FIXTURE_ROW_HEIGHT: u32 = 22;
+const FIXTURE_GAP: u32 = 16;
+const COLUMN_GAP: u32 = 40;
+const SECTION_GAP: u32 = 30;
+const GROUP_ROW_HEIGHT: u32 = 20;
+const GROUP_WIDTH: u32 = 300;
+
+const WINNER_FILL: &str = "#d8f0d8";
+const QUALIFIED_FILL: &str = "#d8f0d8";
+const WILDCARD_FILL: &str = "#f0ecd0";
+const BOX_FILL: &str = "#ffffff";
+const LINE: &str = "#999999";
+
+// Bracket of a whole tournament as one SVG: group tables on top, then the winners bracket with
+// the grand final to its right, then the losers bracket.
+pub fn bracket_svg(tourny_result: &TournamentResult) -> String {
+	let mut elements = Vec::new();
+	elements.push(text(
+		MARGIN,
+		MARGIN + 20,
+		&tourny_result.tournament_name,
+		"font-size=\"20\" font-weight=\"bold\"",
+	));
+
+	let mut y = MARGIN + TITLE_HEIGHT;
+	let mut width = 0;
+
+	if let Some(group_tables) = &tourny_result.group_tables {
+		let mut height = 0;
+		for (i, group_table) in group_tables.iter().enumerate() {
+			let x = MARGIN + i as u32 * (GROUP_WIDTH + COLUMN_GAP);
+			height = height.max(draw_group_table(&mut elements, group_table, x, y));
+			width = width.max(x + GROUP_WIDTH);
+		}
+		y += height + SECTION_GAP;
+	}
+
+	let rounds_of = |stage: Stage| -> Vec<&Round> {
+		tourny_result
+			.rounds
+			.iter()
+			.filter(|r| r.stage == stage)
+			.collect()
+	};
+
+	let winners = rounds_of(Stage::Winners);
+	let grand_final = rounds_of(Stage::GrandFinal);
+	let winners_height = section_height(&winners);
+	let right = draw_section(&mut elements, &winners, MARGIN, y);
+	if !grand_final.is_empty() {
+		// Centered next to the winners bracket.
+		let gf_y = y + winners_height.saturating_sub(section_height(&grand_final)) / 2;
+		let gf_right = draw_section(&mut elements, &grand_final, right + COLUMN_GAP, gf_y);
+		width = width.max(gf_right);
+	}
+	width = width.max(right);
+	y += winners_height + SECTION_GAP;
+
+	let losers = rounds_of(Stage::Losers);
+	if !losers.is_empty() {
+		width = width.max(draw_section(&mut elements, &losers, MARGIN, y));
+		y += section_height(&losers) + SECTION_GAP;
+	}
+
+	let width = width + MARGIN;
+	let height = y - SECTION_GAP + MARGIN;
+	format!(
+		"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+		viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\" font-size=\"13\">\n\
+		<rect width=\"100%\" height=\"100%\" fill=\"#fafafa\"/>\n{}\n</svg>\n",
+		elements.join("\n")
+	)
+}
+
+// Rounds side by side, each with its fixtures spread over the height of the largest round.
+// Returns the right edge of the section.
+fn draw_section(elements: &mut Vec<String>, rounds: &[&Round], left: u32, top: u32) -> u32 {
+	let height = section_height(rounds);
+	let mut x = left;
+	for round in rounds {
+		let name = match round.stage {
+			Stage::GrandFinal => Stage::GrandFinal.to_string(),
+			stage => format!("{} {}", stage.abbreviation(), round.name),
+		};
+		elements.push(text(x, top + 16, &name, "font-weight=\"bold\""));
+
+		let fixtures_top = top + HEADING_HEIGHT;
+		let slot = (height - HEADING_HEIGHT) / round.fixtures.len().max(1) as u32;
+		for (i, fixture) in round.fixtures.iter().enumerate() {
+			let y = fixtures_top + i as u32 * slot + (slot - fixture_height()) / 2;
+			draw_fixture(elements, fixture, x, y);
+		}
+		x += FIXTURE_WIDTH + COLUMN_GAP;
+	}
+
+	x.saturating_sub(COLUMN_GAP).max(left)
+}
+
+fn section_height(rounds: &[&Round]) -> u32 {
+	let most_fixtures = rounds.iter().map(|r| r.fixtures.len()).max().unwrap_or(0) as u32;
+	HEADING_HEIGHT + most_fixtures * (fixture_height() + FIXTURE_GAP)
+}
+
+fn fixture_height() -> u32 {
+	FIXTURE_ROW_HEIGHT * 2
+}
+
+fn draw_fixture(elements: &mut Vec<String>, fixture: &Fixture, x: u32, y: u32) {
+	let winner = fixture.winner().ok().flatten();
+	let rows = [
+		(fixture.team1, fixture.score1, fixture.pen1),
+		(fixture.team2, fixture.score2, fixture.pen2),
+	];
+	for (i, (team, score, pen)) in rows.into_iter().enumerate() {
+		let row_y = y + i as u32 * FIXTURE_ROW_HEIGHT;
+		let won = winner == Some(team);
+		elements.push(rect(
+			x,
+			row_y,
+			FIXTURE_WIDTH,
+			FIXTURE_ROW_HEIGHT,
+			if won { WINNER_FILL } else { BOX_FILL },
+		));
+
+		let weight = if won { "font-weight=\"bold\"" } else { "" };
+		elements.push(text(x + 6, row_y + 15, &team.to_string(), weight));
+		let score = match pen {
+			Some(pen) => format!("{score} ({pen})"),
+			None => score.to_string(),
+		};
+		elements.push(text(
+			x + FIXTURE_WIDTH - 6,
+			row_y + 15,
+			&score,
+			&format!("text-anchor=\"end\" {weight}"),
+		));
+	}
+}
+
+// Returns the height of the table.
+fn draw_group_table(elements: &mut Vec<String>, group_table: &GroupTable, x: u32, y: u32) -> u32 {
+	elements.push(text(
+		x,
+		y + 16,
+		&format!("Group {}", group_table.group),
+		"font-weight=\"bold\"",
+	));
+
+	// Column offsets from the left of the table.
+	let columns: [(&str, u32); 7] = [
+		("Team", 6),
+		("P", 150),
+		("W", 175),
+		("D", 200),
+		("L", 225),
+		("GD", 255),
+		("Pts", 290),
+	];
+	let header_y = y + HEADING_HEIGHT;
+	for (header, offset) in columns {
+		let anchor = if header == "Team" {
+			""
+		} else {
+			"text-anchor=\"end\""
+		};
+		elements.push(text(
+			x + offset,
+			header_y + 14,
+			header,
+			&format!("{anchor} fill=\"#555555\""),
+		));
+	}
+
+	for (i, standing) in group_table.standings.iter().enumerate() {
+		let row_y = header_y + (i as u32 + 1) * GROUP_ROW_HEIGHT;
+		let fill = match standing.status {
+			Qualification::Qualified => QUALIFIED_FILL,
+			Qualification::Wildcard => WILDCARD_FILL,
+			Qualification::Eliminated => BOX_FILL,
+		};
+		elements.push(rect(x, row_y, GROUP_WIDTH, GROUP_ROW_HEIGHT, fill));
+
+		let cells = [
+			format!("{}. {}", standing.position, standing.team),
+			standing.played.to_string(),
+			standing.won.to_string(),
+			standing.drawn.to_string(),
+			standing.lost.to_string(),
+			standing.goal_difference.to_string(),
+			standing.points.to_string(),
+		];
+		for (cell, (header, offset)) in cells.iter().zip(columns) {
+			let anchor = if header == "Team" {
+				""
+			} else {
+				"text-anchor=\"end\""
+			};
+			elements.push(text(x + offset, row_y + 14, cell, anchor));
+		}
+	}
+
+	HEADING_HEIGHT + (group_table.standings.len() as u32 + 1) * GROUP_ROW_HEIGHT
+}
+
+fn rect(x: u32, y: u32, width: u32, height: u32, fill: &str) -> String {
+	format!(
+		"<rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{height}\" fill=\"{fill}\" stroke=\"{LINE}\"/>"
+	)
+}
+
+fn text(x: u32, y: u32, content: &str, attributes: &str) -> String {
+	format!(
+		"<text x=\"{x}\" y=\"{y}\" {attributes}>{}</text>",
+		escape(content)
+	)
+}
+
+// Same rules for SVG and HTML, so the site uses it too.
+pub(crate) fn escape(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+		.replace('\'', "&#39;")
+}
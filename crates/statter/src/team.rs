@@ -1,12 +1,16 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use toml::value::Datetime;
 
-use crate::{fixture::GreatestFixture, tournament::Participation};
+use crate::{
+	bracket::Stage, fixture::GreatestFixture, honours::TrophyCabinet, output::OutputFormat,
+	rankings::Seasons, streaks::Streaks, tournament::Participation,
+};
 use common::{
 	errors::{TeamError, ToolError},
 	PlayerName, TeamName,
 };
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MatchupHistory {
 	pub opponent_name: TeamName,
 	pub goals_against: u32,
@@ -15,11 +19,21 @@ pub struct MatchupHistory {
 	pub penalties_goals_against: u32,
 	pub penalties_goals_for: u32,
 	pub wins: u32,
-	draws: u32,
+	pub draws: u32,
 	pub losses: u32,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub last_meeting: Option<Meeting>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub biggest_win: Option<Meeting>,
+	// Biggest win for the opponent.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub biggest_loss: Option<Meeting>,
+	// Every fixture against the opponent, oldest first.
+	pub meetings: Vec<Meeting>,
 }
 
 impl MatchupHistory {
+	#[allow(clippy::too_many_arguments)]
 	pub fn from(
 		opponent_name: TeamName,
 		goals_against: u32,
@@ -41,6 +55,10 @@ impl MatchupHistory {
 			wins,
 			draws,
 			losses,
+			last_meeting: None,
+			biggest_win: None,
+			biggest_loss: None,
+			meetings: Vec::new(),
 		}
 	}
 
@@ -57,12 +75,82 @@ impl MatchupHistory {
 		self.wins += other.wins;
 		self.draws += other.draws;
 		self.losses += other.losses;
+		for meeting in &other.meetings {
+			self.push_meeting(meeting.clone());
+		}
 		Ok(())
 	}
+
+	// NOTE: Expects meetings to be pushed in the order they were played.
+	pub fn push_meeting(&mut self, meeting: Meeting) {
+		match meeting.result {
+			MeetingResult::Win => {
+				if self
+					.biggest_win
+					.as_ref()
+					.is_none_or(|bw| meeting.dominance() > bw.dominance())
+				{
+					self.biggest_win = Some(meeting.clone());
+				}
+			}
+			MeetingResult::Loss => {
+				if self
+					.biggest_loss
+					.as_ref()
+					.is_none_or(|bl| meeting.dominance() > bl.dominance())
+				{
+					self.biggest_loss = Some(meeting.clone());
+				}
+			}
+			MeetingResult::Draw => (),
+		}
+		self.last_meeting = Some(meeting.clone());
+		self.meetings.push(meeting);
+	}
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, strum_macros::Display)]
+pub enum MeetingResult {
+	Win,
+	Draw,
+	Loss,
+}
+
+// A single fixture against an opponent, seen from the team's side.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Meeting {
+	pub tournament_name: String,
+	#[serde(with = "crate::output::date")]
+	pub date: Datetime,
+	pub stage: Stage,
+	pub round: String,
+	pub result: MeetingResult,
+	pub goals_for: u8,
+	pub goals_against: u8,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub pens_for: Option<u8>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub pens_against: Option<u8>,
+}
+
+impl Meeting {
+	// How one-sided the meeting was: goal margin, then goals scored by the winner, then
+	// shoot-out margin.
+	fn dominance(&self) -> (u8, u8, u8) {
+		let pen_margin = match (self.pens_for, self.pens_against) {
+			(Some(pf), Some(pa)) => pf.abs_diff(pa),
+			_ => 0,
+		};
+		(
+			self.goals_for.abs_diff(self.goals_against),
+			self.goals_for.max(self.goals_against),
+			pen_margin,
+		)
+	}
 }
 
 // Used both as Team stats for tournament and Team stats over time.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Team {
 	pub name: TeamName,
 	pub goals_against: u32,
@@ -73,13 +161,31 @@ pub struct Team {
 	pub wins: u32,
 	pub draws: u32,
 	pub losses: u32,
+	pub clean_sheets: u32,
+	pub failed_to_score: u32,
+	pub goals_against_per_match: f64,
 	pub scorers: Vec<(PlayerName, u32)>,
 	pub assisters: Vec<(PlayerName, u32)>,
+	// (scorer, assister, goals), only known from fixtures with goal events.
+	pub partnerships: Vec<(PlayerName, PlayerName, u32)>,
+	// Clean sheets of the team's goalkeepers, as entered in its fixtures or on its roster.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub keeper_clean_sheets: Option<Vec<(PlayerName, u32)>>,
 	// Don't want these to show up in tournament file.
+	#[serde(skip_serializing_if = "Option::is_none")]
 	greatest_win: Option<GreatestFixture>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	greatest_loss: Option<GreatestFixture>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub matchups: Option<Vec<MatchupHistory>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub participations: Option<Vec<Participation>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub streaks: Option<Streaks>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub trophy_cabinet: Option<TrophyCabinet>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub seasons: Option<Vec<SeasonStats>>,
 }
 
 impl Team {
@@ -94,12 +200,20 @@ impl Team {
 			wins: 0,
 			draws: 0,
 			losses: 0,
+			clean_sheets: 0,
+			failed_to_score: 0,
+			goals_against_per_match: 0.0,
 			greatest_win: None,
 			greatest_loss: None,
 			matchups: None,
 			participations: None,
+			streaks: None,
+			trophy_cabinet: None,
+			seasons: None,
 			scorers: Vec::new(),
 			assisters: Vec::new(),
+			partnerships: Vec::new(),
+			keeper_clean_sheets: None,
 		}
 	}
 
@@ -116,6 +230,9 @@ impl Team {
 		self.wins += other.wins;
 		self.draws += other.draws;
 		self.losses += other.losses;
+		self.clean_sheets += other.clean_sheets;
+		self.failed_to_score += other.failed_to_score;
+		self.update_goals_against_per_match();
 
 		for (other_player, other_goals) in other.scorers.iter() {
 			match self
@@ -141,6 +258,21 @@ impl Team {
 			}
 		}
 
+		for (other_scorer, other_assister, other_goals) in other.partnerships.iter() {
+			match self
+				.partnerships
+				.iter_mut()
+				.find(|(scorer, assister, _)| scorer == other_scorer && assister == other_assister)
+			{
+				Some((_, _, goals)) => *goals += other_goals,
+				None => self.partnerships.push((
+					other_scorer.to_owned(),
+					other_assister.to_owned(),
+					*other_goals,
+				)),
+			}
+		}
+
 		if let Some(other_greatest_loss) = other.greatest_loss.as_ref() {
 			self.try_add_greatest_loss(other_greatest_loss)?;
 		}
@@ -160,7 +292,7 @@ impl Team {
 						.iter_mut()
 						.find(|m| m.opponent_name == matchup.opponent_name)
 					{
-						matchup_self.add(&matchup)?;
+						matchup_self.add(matchup)?;
 					} else {
 						matchups_self.push(matchup.clone());
 					}
@@ -174,8 +306,21 @@ impl Team {
 		Ok(())
 	}
 
-	pub fn filename(&self) -> String {
-		self.name.to_string().to_lowercase().replace(' ', "-") + ".toml"
+	pub fn matches_played(&self) -> u32 {
+		self.wins + self.draws + self.losses
+	}
+
+	// Rounded to 2 decimals, so it's readable in the stats files.
+	pub fn update_goals_against_per_match(&mut self) {
+		let matches_played = self.matches_played();
+		self.goals_against_per_match = match matches_played {
+			0 => 0.0,
+			n => (self.goals_against as f64 / n as f64 * 100.0).round() / 100.0,
+		};
+	}
+
+	pub fn filename(&self, format: OutputFormat) -> String {
+		format!("{}.{}", team_slug(self.name), format.extension())
 	}
 
 	pub fn get_greatest_loss(&self) -> Option<&GreatestFixture> {
@@ -278,9 +423,97 @@ impl Team {
 	}
 }
 
-#[derive(Clone, Debug, Serialize)]
+// A team's stats from a single season, taken from a Team that only added that season's
+// tournaments.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SeasonStats {
+	pub season_num: u8,
+	pub ranking_points: u32,
+	pub rank: u8,
+	pub goals_against: u32,
+	pub goals_for: u32,
+	pub wins: u32,
+	pub draws: u32,
+	pub losses: u32,
+	pub clean_sheets: u32,
+	pub failed_to_score: u32,
+	pub goals_against_per_match: f64,
+	pub scorers: Vec<(PlayerName, u32)>,
+	pub assisters: Vec<(PlayerName, u32)>,
+	pub matchups: Vec<MatchupHistory>,
+}
+
+impl SeasonStats {
+	pub fn from(season_num: u8, season_team: Team, seasons: &Seasons) -> Self {
+		let (ranking_points, rank) = seasons
+			.team_standing(season_num, season_team.name)
+			.unwrap_or_default();
+		let mut scorers = season_team.scorers;
+		sort_player_counts(&mut scorers);
+		let mut assisters = season_team.assisters;
+		sort_player_counts(&mut assisters);
+		let mut matchups = season_team.matchups.unwrap_or_default();
+		matchups.sort_unstable_by_key(|mh| mh.opponent_name);
+
+		SeasonStats {
+			season_num,
+			ranking_points,
+			rank,
+			goals_against: season_team.goals_against,
+			goals_for: season_team.goals_for,
+			wins: season_team.wins,
+			draws: season_team.draws,
+			losses: season_team.losses,
+			clean_sheets: season_team.clean_sheets,
+			failed_to_score: season_team.failed_to_score,
+			goals_against_per_match: season_team.goals_against_per_match,
+			scorers,
+			assisters,
+			matchups,
+		}
+	}
+}
+
+// File name friendly version of the team name, e.g. "big-funky".
+pub fn team_slug(name: TeamName) -> String {
+	name.to_string().to_lowercase().replace(' ', "-")
+}
+
+// Most goals or assists first, then by name.
+pub fn sort_player_counts(players: &mut [(PlayerName, u32)]) {
+	players.sort_unstable_by(|(a_name, a_count), (b_name, b_count)| {
+		b_count.cmp(a_count).then(
+			a_name
+				.to_ascii_lowercase()
+				.cmp(&b_name.to_ascii_lowercase()),
+		)
+	});
+}
+
+// Most goals first, then by scorer and assister.
+pub fn sort_partnerships(partnerships: &mut [(PlayerName, PlayerName, u32)]) {
+	partnerships.sort_unstable_by(
+		|(a_scorer, a_assister, a_goals), (b_scorer, b_assister, b_goals)| {
+			b_goals
+				.cmp(a_goals)
+				.then(
+					a_scorer
+						.to_ascii_lowercase()
+						.cmp(&b_scorer.to_ascii_lowercase()),
+				)
+				.then(
+					a_assister
+						.to_ascii_lowercase()
+						.cmp(&b_assister.to_ascii_lowercase()),
+				)
+		},
+	);
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TeamPlacement {
 	pub team: Team,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub placement: Option<u8>,
 	#[serde(skip_serializing)]
 	pub head_to_head: Option<u8>,
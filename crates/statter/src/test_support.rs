@@ -0,0 +1,100 @@
+// Builders shared by the unit tests, so cups and identities are written the same way
+// everywhere.
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tokio::runtime::Runtime;
+
+use crate::entry::run_cup;
+use crate::players::PlayerIdentities;
+use crate::tournament::TournamentResult;
+use common::errors::ToolError;
+
+// A folder under the system temp folder, removed again when dropped.
+pub struct TempDir {
+	path: PathBuf,
+}
+
+impl TempDir {
+	pub fn new() -> Self {
+		static COUNT: AtomicUsize = AtomicUsize::new(0);
+		let path = std::env::temp_dir().join(format!(
+			"statter-test-{}-{}",
+			std::process::id(),
+			COUNT.fetch_add(1, Ordering::Relaxed)
+		));
+		std::fs::create_dir_all(&path).unwrap();
+		Self { path }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	// Writes a file, creating the folders it's in, and returns its path.
+	pub fn write(&self, file: &str, contents: &str) -> PathBuf {
+		let path = self.path.join(file);
+		std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+		std::fs::write(&path, contents).unwrap();
+		path
+	}
+}
+
+impl Drop for TempDir {
+	fn drop(&mut self) {
+		let _ = std::fs::remove_dir_all(&self.path);
+	}
+}
+
+pub fn block_on<F: Future>(future: F) -> F::Output {
+	Runtime::new().unwrap().block_on(future)
+}
+
+pub fn identities(identities_toml: &str) -> Result<PlayerIdentities, ToolError> {
+	let folder = TempDir::new();
+	let path = folder.write("players.toml", identities_toml);
+	block_on(PlayerIdentities::load(&path, None))
+}
+
+// A cup file with the given tables after its header.
+pub fn cup(playoff_teams: u8, has_losers: bool, tables: &[String]) -> String {
+	format!(
+		"tournament_name = \"Test Cup\"\nseason_num = 1\ndate = 2024-01-01\n\
+		has_losers = {has_losers}\nplayoff_teams = {playoff_teams}\n\
+		point_system = \"TennisV1\"\n\n{}",
+		tables.concat()
+	)
+}
+
+// A fixture in the given table, e.g. "brackets.winners", with a made-up scorer per goal.
+pub fn fixture(table: &str, team1: &str, team2: &str, score1: u8, score2: u8) -> String {
+	let scorers = |goals: u8| vec!["\"Scorer\""; goals as usize].join(", ");
+	format!(
+		"[[{table}]]\nteam1 = \"{team1}\"\nteam2 = \"{team2}\"\nscore1 = {score1}\n\
+		score2 = {score2}\nscorers1 = [{}]\nscorers2 = [{}]\nassisters1 = []\n\
+		assisters2 = []\n\n",
+		scorers(score1),
+		scorers(score2)
+	)
+}
+
+pub fn group_fixture(group: &str, team1: &str, team2: &str, score1: u8, score2: u8) -> String {
+	fixture("brackets.groups", team1, team2, score1, score2).replacen(
+		"\n\n",
+		&format!("\ngroup_id = \"{group}\"\n\n"),
+		1,
+	)
+}
+
+// An entry of the cup's teams list.
+pub fn participant(name: &str, group: Option<&str>) -> String {
+	match group {
+		Some(group) => format!("[[teams]]\nname = \"{name}\"\ngroup = \"{group}\"\n\n"),
+		None => format!("[[teams]]\nname = \"{name}\"\n\n"),
+	}
+}
+
+pub fn run(cup: &str) -> TournamentResult {
+	run_cup(toml::from_str(cup).unwrap(), &PlayerIdentities::default()).unwrap()
+}
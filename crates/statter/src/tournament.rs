@@ -1,14 +1,16 @@
 use core::cmp::min;
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::{Deref, DerefMut};
 
 use serde::{Deserialize, Serialize};
 use toml::value::Datetime;
 
+use crate::bracket::{Round, TeamPath};
 use crate::fixture::{Fixture, GreatestFixture};
+use crate::players::PlayerIdentities;
 use crate::rankings::RankedTeam;
-use crate::team::{MatchupHistory, Team, TeamPlacement};
+use crate::team::{MatchupHistory, Meeting, MeetingResult, Team, TeamPlacement};
 use common::{
 	errors::{ToolError, TournamentError},
 	PlayerName, TeamName,
@@ -21,7 +23,19 @@ pub struct Brackets {
 	pub groups: Option<Vec<Fixture>>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Copy, Eq, Hash, PartialEq, Debug)]
+#[derive(
+	Deserialize,
+	Serialize,
+	Clone,
+	Copy,
+	Eq,
+	Hash,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	Debug,
+	strum_macros::Display,
+)]
 pub enum GroupID {
 	A,
 	B,
@@ -43,7 +57,7 @@ impl GroupTeams {
 		let mut failed_team1 = TeamName::Unknown;
 		let mut failed_team2 = TeamName::Unknown;
 		self.teams.sort_unstable_by(|b, a| {
-			let order = a.cmp(&b);
+			let order = a.cmp(b);
 			if order == Ordering::Equal {
 				has_failed_to_order_team = true;
 				failed_team1 = a.team;
@@ -84,26 +98,38 @@ struct GroupTeam {
 	group: GroupID,
 	team: TeamName,
 	points: u8,
+	wins: u8,
+	draws: u8,
+	losses: u8,
 	goals_for: u8,
 	goals_against: u8,
 	head_to_head: Option<u8>,
 }
 
 impl GroupTeam {
+	fn new(group: GroupID, team: TeamName) -> Self {
+		Self {
+			group,
+			team,
+			points: 0,
+			wins: 0,
+			draws: 0,
+			losses: 0,
+			goals_for: 0,
+			goals_against: 0,
+			head_to_head: None,
+		}
+	}
+
 	fn from_fixture_result(
 		group: GroupID,
 		team: TeamName,
 		goals_for: u8,
 		goals_against: u8,
 	) -> Self {
-		Self {
-			group,
-			team,
-			points: Self::points_from_fixture_result(goals_for, goals_against),
-			goals_for,
-			goals_against,
-			head_to_head: None,
-		}
+		let mut group_team = Self::new(group, team);
+		group_team.add_from_fixture_result(goals_for, goals_against);
+		group_team
 	}
 
 	fn points_from_fixture_result(goals_for: u8, goals_against: u8) -> u8 {
@@ -120,6 +146,75 @@ impl GroupTeam {
 		self.points += Self::points_from_fixture_result(goals_for, goals_against);
 		self.goals_for += goals_for;
 		self.goals_against += goals_against;
+		match goals_for.cmp(&goals_against) {
+			Ordering::Greater => self.wins += 1,
+			Ordering::Equal => self.draws += 1,
+			Ordering::Less => self.losses += 1,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, strum_macros::Display)]
+pub enum Qualification {
+	Qualified,
+	Wildcard,
+	Eliminated,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GroupStanding {
+	pub position: u8,
+	pub team: TeamName,
+	pub played: u8,
+	pub won: u8,
+	pub drawn: u8,
+	pub lost: u8,
+	pub goals_for: u8,
+	pub goals_against: u8,
+	pub goal_difference: i16,
+	pub points: u8,
+	pub status: Qualification,
+}
+
+// Final table of a group, ordered from first to last.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GroupTable {
+	pub group: GroupID,
+	pub standings: Vec<GroupStanding>,
+}
+
+impl GroupTable {
+	fn from(
+		group: GroupID,
+		sorted_teams: &[GroupTeam],
+		qualifying_teams_per_group: usize,
+		wildcards: &[TeamName],
+	) -> Self {
+		let standings = sorted_teams
+			.iter()
+			.enumerate()
+			.map(|(i, gt)| GroupStanding {
+				position: i as u8 + 1,
+				team: gt.team,
+				played: gt.wins + gt.draws + gt.losses,
+				won: gt.wins,
+				drawn: gt.draws,
+				lost: gt.losses,
+				goals_for: gt.goals_for,
+				goals_against: gt.goals_against,
+				goal_difference: gt.goals_for as i16 - gt.goals_against as i16,
+				points: gt.points,
+				status: if i < qualifying_teams_per_group {
+					Qualification::Qualified
+				} else if wildcards.contains(&gt.team) {
+					Qualification::Wildcard
+				} else {
+					Qualification::Eliminated
+				},
+			})
+			.collect();
+
+		Self { group, standings }
 	}
 }
 
@@ -225,7 +320,7 @@ impl<'a> GroupStage<'a> {
 			for h2h_decider in h2h {
 				let team_score = team_scores
 					.get_mut(&h2h_decider.team)
-					.expect(&format!("{:?}", h2h_decider.team));
+					.unwrap_or_else(|| panic!("{:?}", h2h_decider.team));
 				team_score.head_to_head = Some(h2h_decider.decider_points)
 			}
 		}
@@ -243,16 +338,18 @@ impl<'a> GroupStage<'a> {
 			GroupTeams::from(Vec::with_capacity(self.tournament.playoff_teams as usize));
 
 		// Add all the qualifying and wildcard candidate teams.
+		let mut sorted_groups = Vec::with_capacity(groups_seen.len());
 		for group in &groups_seen {
 			let mut group_teams = GroupTeams::from(
 				team_scores
 					.values()
 					.filter(|gt| &gt.group == group)
-					.map(|gt| gt.clone())
+					.cloned()
 					.collect(),
 			);
 
 			group_teams.sort_teams(&self.tournament.tournament_name)?;
+			sorted_groups.push((*group, group_teams.to_vec()));
 
 			let not_qualified = group_teams.split_off(qualifying_teams_per_group);
 			wildcard_candidates.push(
@@ -269,6 +366,17 @@ impl<'a> GroupStage<'a> {
 		// Sort candidates and add the qualifying wildcard candidates to qualifying teams.
 		wildcard_candidates.sort_teams(&self.tournament.tournament_name)?;
 		wildcard_candidates.drain(wildcards_count..);
+
+		// Keep the final group tables for the tournament results.
+		let wildcards: Vec<TeamName> = wildcard_candidates.iter().map(|gt| gt.team).collect();
+		let mut group_tables: Vec<GroupTable> = sorted_groups
+			.iter()
+			.map(|(group, teams)| {
+				GroupTable::from(*group, teams, qualifying_teams_per_group, &wildcards)
+			})
+			.collect();
+		group_tables.sort_unstable_by_key(|gt| gt.group);
+
 		qualifying_teams.append(&mut wildcard_candidates);
 
 		// Give placements to all of the eliminated teams.
@@ -276,7 +384,7 @@ impl<'a> GroupStage<'a> {
 			team_scores
 				.values()
 				.filter(|gt| !qualifying_teams.contains(gt))
-				.map(|gt| gt.clone())
+				.cloned()
 				.collect(),
 		);
 		eliminated_teams.sort_teams(&self.tournament.tournament_name)?;
@@ -285,7 +393,11 @@ impl<'a> GroupStage<'a> {
 			self.placements.set_placement(gt.team, placement as u8);
 		}
 
-		Ok(PlayoffStage::from_groups(self, qualifying_teams))
+		Ok(PlayoffStage::from_groups(
+			self,
+			qualifying_teams,
+			group_tables,
+		))
 	}
 }
 
@@ -295,17 +407,37 @@ pub struct HeadToHead {
 	pub decider_points: u8,
 }
 
-#[derive(Clone, Debug, Serialize)]
+// A fixture that's still to be played. Listed in playing order while a tournament is in
+// progress, and matched up with the fixtures as they're entered.
+#[derive(Deserialize)]
+pub struct ScheduledFixture {
+	pub team1: TeamName,
+	pub team2: TeamName,
+	// E.g. "Group A" or "Semi-final".
+	pub round: Option<String>,
+}
+
+// A team declared up front in the cup file, and the group it plays in (if any).
+#[derive(Deserialize)]
+pub struct Participant {
+	pub name: TeamName,
+	pub group: Option<GroupID>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Participation {
-	tournament_name: String,
+	pub tournament_name: String,
+	pub season_num: u8,
+	#[serde(with = "crate::output::date")]
 	pub date: Datetime,
-	placement: u8,
+	pub placement: u8,
 }
 
 impl Participation {
-	pub fn new(tournament_name: String, placement: u8, date: Datetime) -> Self {
+	pub fn new(tournament_name: String, season_num: u8, placement: u8, date: Datetime) -> Self {
 		Participation {
 			tournament_name,
+			season_num,
 			date,
 			placement,
 		}
@@ -317,6 +449,7 @@ struct PlayoffStage<'a> {
 	tournament: &'a Tournament,
 	// Only if groups are used.
 	qualifying_teams: Option<GroupTeams>,
+	group_tables: Option<Vec<GroupTable>>,
 }
 
 impl<'a> PlayoffStage<'a> {
@@ -325,14 +458,20 @@ impl<'a> PlayoffStage<'a> {
 			placements: TournamentPlacements::new(),
 			tournament,
 			qualifying_teams: None,
+			group_tables: None,
 		}
 	}
 
-	fn from_groups(groups: GroupStage<'a>, qualifying_teams: GroupTeams) -> Self {
+	fn from_groups(
+		groups: GroupStage<'a>,
+		qualifying_teams: GroupTeams,
+		group_tables: Vec<GroupTable>,
+	) -> Self {
 		Self {
 			placements: groups.placements,
 			tournament: groups.tournament,
 			qualifying_teams: Some(qualifying_teams),
+			group_tables: Some(group_tables),
 		}
 	}
 
@@ -380,7 +519,7 @@ impl<'a> PlayoffStage<'a> {
 				let tp = self
 					.placements
 					.get_mut(&h2h_decider.team)
-					.expect(&format!("{:?}", h2h_decider.team));
+					.unwrap_or_else(|| panic!("{:?}", h2h_decider.team));
 				tp.head_to_head = Some(h2h_decider.decider_points);
 			}
 		}
@@ -427,8 +566,7 @@ impl<'a> PlayoffStage<'a> {
 							Ok(_) => std::cmp::Ordering::Greater,
 							Err(_) => {
 								if sort_error.is_ok() {
-									sort_error =
-										Err(TournamentError::SortingPreviousFixtureError.into());
+									sort_error = Err(TournamentError::SortingPreviousFixtureError);
 								}
 								std::cmp::Ordering::Equal
 							}
@@ -451,8 +589,7 @@ impl<'a> PlayoffStage<'a> {
 								self.tournament.tournament_name.clone(),
 								a.team.name,
 								b.team.name,
-							)
-							.into());
+							));
 						}
 
 						b_team.cmp(&a_team)
@@ -498,7 +635,7 @@ impl<'a> PlayoffStage<'a> {
 	fn grand_final(&mut self) -> Result<(), ToolError> {
 		let gf_fixtures = self.tournament.grand_final.as_ref().unwrap();
 
-		if gf_fixtures.len() == 0 || gf_fixtures.len() > 2 {
+		if gf_fixtures.is_empty() || gf_fixtures.len() > 2 {
 			return Err(TournamentError::InvalidGrandFinal(
 				self.tournament.tournament_name.clone(),
 				format!(
@@ -634,7 +771,7 @@ impl<'a> PlayoffStage<'a> {
 			stage_fixtures -= 1;
 			teams_to_subtract += 1; // We need to use teams_left unchanged for placements...
 			if stage_fixtures == 0 {
-				stages_left = stages_left - 1;
+				stages_left -= 1;
 				stage_fixtures = stage_fixture_count(stages_left);
 				teams_left -= teams_to_subtract; // ...so we only subtract when changing stages.
 				teams_to_subtract = 0;
@@ -701,7 +838,7 @@ impl<'a> PlayoffStage<'a> {
 			stage_fixture_count -= 1;
 
 			if stage_fixture_count == 0 {
-				stages_left = stages_left - 1;
+				stages_left -= 1;
 				teams_left = 2u8.pow(stages_left as u32);
 				stage_fixture_count = teams_left / 2;
 			}
@@ -769,46 +906,301 @@ pub struct Tournament {
 	pub brackets: Brackets,
 	pub grand_final: Option<Vec<Fixture>>,
 	pub head_to_head: Option<Vec<HeadToHead>>,
+	pub teams: Option<Vec<Participant>>,
+	pub schedule: Option<Vec<ScheduledFixture>>,
 }
 
 impl Tournament {
-	pub fn run(&self) -> Result<Vec<TeamPlacement>, ToolError> {
+	pub fn run(&self) -> Result<(Vec<TeamPlacement>, Option<Vec<GroupTable>>), ToolError> {
+		self.validate_participants()?;
+
 		let mut playoffs = match self.brackets.groups {
 			Some(_) => GroupStage::from(self).run()?,
 			None => PlayoffStage::from(self),
 		};
 
-		playoffs.run()
+		let team_placements = playoffs.run()?;
+		Ok((team_placements, playoffs.group_tables))
+	}
+
+	// Group tables as they stand, for a tournament that's still being played. Listed teams that
+	// haven't played yet are included, and teams still tied are ordered by name. Qualification
+	// is as if the group stage ended now, without wildcards.
+	pub fn current_group_tables(&self) -> Vec<GroupTable> {
+		let mut team_scores: HashMap<TeamName, GroupTeam> = HashMap::new();
+		for participant in self.teams.iter().flatten() {
+			if let Some(group) = participant.group {
+				team_scores.insert(participant.name, GroupTeam::new(group, participant.name));
+			}
+		}
+		for fixture in self.brackets.groups.iter().flatten() {
+			let Some(group) = fixture.group else {
+				continue;
+			};
+			team_scores
+				.entry(fixture.team1)
+				.or_insert(GroupTeam::new(group, fixture.team1))
+				.add_from_fixture_result(fixture.score1, fixture.score2);
+			team_scores
+				.entry(fixture.team2)
+				.or_insert(GroupTeam::new(group, fixture.team2))
+				.add_from_fixture_result(fixture.score2, fixture.score1);
+		}
+		for h2h_decider in self.head_to_head.iter().flatten() {
+			if let Some(team_score) = team_scores.get_mut(&h2h_decider.team) {
+				team_score.head_to_head = Some(h2h_decider.decider_points);
+			}
+		}
+
+		let mut groups: BTreeMap<GroupID, Vec<GroupTeam>> = BTreeMap::new();
+		for group_team in team_scores.into_values() {
+			groups.entry(group_team.group).or_default().push(group_team);
+		}
+		let qualifying_teams_per_group = self.playoff_teams as usize / groups.len().max(1);
+		groups
+			.into_iter()
+			.map(|(group, mut teams)| {
+				teams.sort_unstable_by(|a, b| b.cmp(a).then(a.team.cmp(&b.team)));
+				GroupTable::from(group, &teams, qualifying_teams_per_group, &[])
+			})
+			.collect()
+	}
+
+	pub fn fixtures(&self) -> impl Iterator<Item = &Fixture> {
+		self.brackets
+			.groups
+			.iter()
+			.flatten()
+			.chain(&self.brackets.winners)
+			.chain(self.brackets.losers.iter().flatten())
+			.chain(self.grand_final.iter().flatten())
+	}
+
+	pub fn fixtures_mut(&mut self) -> impl Iterator<Item = &mut Fixture> {
+		self.brackets
+			.groups
+			.iter_mut()
+			.flatten()
+			.chain(&mut self.brackets.winners)
+			.chain(self.brackets.losers.iter_mut().flatten())
+			.chain(self.grand_final.iter_mut().flatten())
+	}
+
+	// Needs to happen before the tournament is run, so fixtures with goal events have their
+	// scorers and assisters, and after players are resolved, so aliases match.
+	pub fn link_goal_events(&mut self) -> Result<(), ToolError> {
+		self.fixtures_mut().try_for_each(Fixture::link_goal_events)
+	}
+
+	pub fn resolve_players(&mut self, identities: &PlayerIdentities) {
+		self.fixtures_mut()
+			.for_each(|fixture| fixture.resolve_players(identities));
+	}
+
+	// Catches typos in team names, which would otherwise quietly add an extra team.
+	fn validate_participants(&self) -> Result<(), ToolError> {
+		let Some(participants) = &self.teams else {
+			return Ok(());
+		};
+
+		let mut listed: HashMap<TeamName, Option<GroupID>> = HashMap::new();
+		for participant in participants {
+			if listed.insert(participant.name, participant.group).is_some() {
+				return Err(TournamentError::DuplicateParticipant(
+					self.tournament_name.clone(),
+					participant.name,
+				)
+				.into());
+			}
+		}
+
+		let mut played: HashSet<TeamName> = HashSet::new();
+		for fixture in self.fixtures() {
+			for (team, opponent) in [
+				(fixture.team1, fixture.team2),
+				(fixture.team2, fixture.team1),
+			] {
+				if !listed.contains_key(&team) {
+					return Err(TournamentError::UnlistedParticipant(
+						self.tournament_name.clone(),
+						team,
+						opponent,
+					)
+					.into());
+				}
+				played.insert(team);
+			}
+		}
+
+		if let Some(participant) = participants.iter().find(|p| !played.contains(&p.name)) {
+			return Err(TournamentError::ParticipantDidNotPlay(
+				self.tournament_name.clone(),
+				participant.name,
+			)
+			.into());
+		}
+
+		// Every group fixture has to be between declared members of that group.
+		let mut played_in_group: HashSet<(TeamName, GroupID)> = HashSet::new();
+		for fixture in self.brackets.groups.iter().flatten() {
+			let Some(group) = fixture.group else {
+				continue;
+			};
+			for team in [fixture.team1, fixture.team2] {
+				match listed[&team] {
+					Some(listed_group) if listed_group != group => {
+						return Err(TournamentError::WrongGroup(
+							self.tournament_name.clone(),
+							team,
+							group.to_string(),
+							listed_group.to_string(),
+						)
+						.into())
+					}
+					_ => played_in_group.insert((team, group)),
+				};
+			}
+		}
+
+		// And every declared member has to have played in their group.
+		for participant in participants {
+			if let Some(group) = participant.group {
+				if !played_in_group.contains(&(participant.name, group)) {
+					return Err(TournamentError::MissingGroupMember(
+						self.tournament_name.clone(),
+						participant.name,
+						group.to_string(),
+					)
+					.into());
+				}
+			}
+		}
+
+		Ok(())
 	}
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct TournamentResult {
 	pub tournament_name: String,
 	pub season_num: u8,
+	#[serde(with = "crate::output::date")]
 	pub date: Datetime,
 	point_system: PointSystem,
 	pub team_placements: Vec<TeamPlacement>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub group_tables: Option<Vec<GroupTable>>,
+	pub rounds: Vec<Round>,
+	pub paths: Vec<TeamPath>,
 	pub scorers: Vec<(PlayerName, u32, TeamName)>,
 	pub assisters: Vec<(PlayerName, u32, TeamName)>,
+	// (scorer, assister, goals, team)
+	pub partnerships: Vec<(PlayerName, PlayerName, u32, TeamName)>,
 }
 
 impl TournamentResult {
 	pub fn from(
-		team_placements: Vec<TeamPlacement>,
+		mut team_placements: Vec<TeamPlacement>,
+		group_tables: Option<Vec<GroupTable>>,
 		tourny: Tournament,
 		goal_scorers: Vec<(PlayerName, u32, TeamName)>,
 		assisters: Vec<(PlayerName, u32, TeamName)>,
-	) -> Self {
-		Self {
+	) -> Result<Self, ToolError> {
+		let rounds = Round::all_from(&tourny);
+		Self::add_meetings(&mut team_placements, &rounds, &tourny)?;
+		let paths = TeamPath::all_from(&rounds, group_tables.as_ref(), &team_placements);
+		let partnerships = team_placements
+			.iter()
+			.flat_map(|tp| {
+				tp.team
+					.partnerships
+					.iter()
+					.map(|(scorer, assister, goals)| {
+						(scorer.clone(), assister.clone(), *goals, tp.team.name)
+					})
+			})
+			.collect();
+
+		Ok(Self {
 			tournament_name: tourny.tournament_name,
 			season_num: tourny.season_num,
 			date: tourny.date,
 			point_system: tourny.point_system,
 			team_placements,
+			group_tables,
+			rounds,
+			paths,
 			scorers: goal_scorers,
 			assisters,
+			partnerships,
+		})
+	}
+
+	// Add every fixture to the matchup histories of both teams, now that the stage and round
+	// of each fixture is known.
+	fn add_meetings(
+		team_placements: &mut [TeamPlacement],
+		rounds: &[Round],
+		tourny: &Tournament,
+	) -> Result<(), ToolError> {
+		for round in rounds {
+			for fixture in &round.fixtures {
+				let winner = fixture.winner()?;
+				for (team, opponent, goals_for, goals_against, pens_for, pens_against) in [
+					(
+						fixture.team1,
+						fixture.team2,
+						fixture.score1,
+						fixture.score2,
+						fixture.pen1,
+						fixture.pen2,
+					),
+					(
+						fixture.team2,
+						fixture.team1,
+						fixture.score2,
+						fixture.score1,
+						fixture.pen2,
+						fixture.pen1,
+					),
+				] {
+					let Some(matchup) = team_placements
+						.iter_mut()
+						.find(|tp| tp.team.name == team)
+						.and_then(|tp| tp.team.matchups.as_mut())
+						.and_then(|m| m.iter_mut().find(|m| m.opponent_name == opponent))
+					else {
+						continue;
+					};
+
+					matchup.push_meeting(Meeting {
+						tournament_name: tourny.tournament_name.clone(),
+						date: tourny.date,
+						stage: round.stage,
+						round: round.name.clone(),
+						result: match winner {
+							Some(w) if w == team => MeetingResult::Win,
+							Some(_) => MeetingResult::Loss,
+							None => MeetingResult::Draw,
+						},
+						goals_for,
+						goals_against,
+						pens_for,
+						pens_against,
+					});
+				}
+			}
 		}
+
+		Ok(())
+	}
+
+	// File name friendly version of the tournament name, e.g. "big-funny-cup-3-return".
+	pub fn slug(&self) -> String {
+		self.tournament_name
+			.to_lowercase()
+			.replace(' ', "-")
+			.replace(|c: char| !c.is_ascii() || c == ':', "")
 	}
 
 	pub fn get_teams_ranked(&self) -> Vec<RankedTeam> {
@@ -818,7 +1210,7 @@ impl TournamentResult {
 			.map(|tp| {
 				let placement = tp.placement.unwrap();
 				RankedTeam {
-					name: tp.team.name.clone(),
+					name: tp.team.name,
 					ranking_points: vec![points.get(placement as usize - 1)],
 					ranks: Vec::new(),
 				}
@@ -892,13 +1284,15 @@ impl TournamentPlacements {
 				&fixture.assisters2,
 			),
 		};
-		if scorers_for.len() != goals_for as usize {
+		// Own goals are only known from goal events, and aren't credited to a scorer.
+		let own_goals = fixture.own_goals(is_team1);
+		if scorers_for.len() + own_goals != goals_for as usize {
 			return Err(TournamentError::GoalsMismatch(
 				tournament_name.to_owned(),
 				team_name,
 				opponent_name,
 				goals_for,
-				scorers_for.len(),
+				scorers_for.len() + own_goals,
 			)
 			.into());
 		}
@@ -920,6 +1314,12 @@ impl TournamentPlacements {
 
 		team_entry.team.goals_for += goals_for as u32;
 		team_entry.team.goals_against += goals_against as u32;
+		if goals_against == 0 {
+			team_entry.team.clean_sheets += 1;
+		}
+		if goals_for == 0 {
+			team_entry.team.failed_to_score += 1;
+		}
 
 		for scorer in scorers_for {
 			match team_entry
@@ -945,6 +1345,23 @@ impl TournamentPlacements {
 			}
 		}
 
+		for (scorer, assister) in fixture.partnerships(is_team1) {
+			match team_entry
+				.team
+				.partnerships
+				.iter_mut()
+				.find(|(s, a, _)| s == scorer && a == assister)
+			{
+				Some((_, _, goals)) => *goals += 1,
+				None => {
+					team_entry
+						.team
+						.partnerships
+						.push((scorer.to_owned(), assister.to_owned(), 1))
+				}
+			}
+		}
+
 		// Add penalties_played, penalties_goals_against, penalties_goals_for.
 		let (penalties_played, penalties_goals_against, penalties_goals_for) =
 			match (pen_goals_for, pen_goals_against) {
@@ -981,9 +1398,10 @@ impl TournamentPlacements {
 				(0, 1, 0)
 			}
 		};
+		team_entry.team.update_goals_against_per_match();
 
 		// Add greatest_{win/loss}.
-		let maybe_greatest = GreatestFixture::from(&fixture, tournament_name);
+		let maybe_greatest = GreatestFixture::from(fixture, tournament_name);
 		team_entry.team.try_add_greatest_win(&maybe_greatest)?;
 		team_entry.team.try_add_greatest_loss(&maybe_greatest)?;
 
@@ -1032,3 +1450,123 @@ impl DerefMut for TournamentPlacements {
 		&mut self.placements
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::overlay::load_live_tournament;
+	use crate::players::PlayerIdentities;
+	use crate::test_support::{block_on, cup, fixture, group_fixture, participant, TempDir};
+
+	fn listed() -> Vec<String> {
+		vec![
+			participant("Cursed", Some("A")),
+			participant("Moai", Some("A")),
+			participant("Vidya", Some("B")),
+			participant("Autoism", Some("B")),
+		]
+	}
+
+	fn played(final_opponent: &str) -> Vec<String> {
+		vec![
+			group_fixture("A", "Cursed", "Moai", 0, 0),
+			group_fixture("B", "Vidya", "Autoism", 0, 0),
+			fixture("brackets.winners", "Cursed", final_opponent, 0, 0),
+		]
+	}
+
+	fn validate(participants: &[String], fixtures: &[String]) -> Result<(), ToolError> {
+		let cup = cup(2, false, &[participants, fixtures].concat());
+		toml::from_str::<Tournament>(&cup)
+			.unwrap()
+			.validate_participants()
+	}
+
+	#[test]
+	fn listed_participants_pass() {
+		assert!(validate(&listed(), &played("Vidya")).is_ok());
+	}
+
+	#[test]
+	fn duplicate_participant() {
+		let mut participants = listed();
+		participants.push(participant("Moai", Some("A")));
+		assert!(matches!(
+			validate(&participants, &played("Vidya")),
+			Err(ToolError::TournamentError(
+				TournamentError::DuplicateParticipant(_, TeamName::Moai)
+			))
+		));
+	}
+
+	#[test]
+	fn unlisted_participant() {
+		assert!(matches!(
+			validate(&listed(), &played("Bone Zone")),
+			Err(ToolError::TournamentError(
+				TournamentError::UnlistedParticipant(_, TeamName::BoneZone, TeamName::Cursed)
+			))
+		));
+	}
+
+	#[test]
+	fn participant_did_not_play() {
+		let mut participants = listed();
+		participants.push(participant("Bone Zone", None));
+		assert!(matches!(
+			validate(&participants, &played("Vidya")),
+			Err(ToolError::TournamentError(
+				TournamentError::ParticipantDidNotPlay(_, TeamName::BoneZone)
+			))
+		));
+	}
+
+	#[test]
+	fn wrong_group() {
+		let mut participants = listed();
+		participants[1] = participant("Moai", Some("B"));
+		assert!(matches!(
+			validate(&participants, &played("Vidya")),
+			Err(ToolError::TournamentError(TournamentError::WrongGroup(
+				_,
+				TeamName::Moai,
+				_,
+				_
+			)))
+		));
+	}
+
+	#[test]
+	fn missing_group_member() {
+		let mut participants = listed();
+		participants.push(participant("Bone Zone", Some("A")));
+		assert!(matches!(
+			validate(&participants, &played("Bone Zone")),
+			Err(ToolError::TournamentError(
+				TournamentError::MissingGroupMember(_, TeamName::BoneZone, _)
+			))
+		));
+	}
+
+	#[test]
+	fn only_live_cups_can_lack_a_winners_bracket() {
+		let before_playoffs = cup(
+			2,
+			false,
+			&[
+				group_fixture("A", "Cursed", "Moai", 0, 0),
+				group_fixture("B", "Vidya", "Autoism", 0, 0),
+			],
+		);
+
+		let error = toml::from_str::<Tournament>(&before_playoffs)
+			.err()
+			.unwrap();
+		assert!(error.to_string().contains("winners"));
+
+		let folder = TempDir::new();
+		let path = folder.write("live.toml", &before_playoffs);
+		let live = block_on(load_live_tournament(&path, &PlayerIdentities::default())).unwrap();
+		assert!(live.brackets.winners.is_empty());
+	}
+}
@@ -0,0 +1,40 @@
+use std::path::{Path, PathBuf};
+
+use iced::task::{sipper, Sipper};
+use tokio::time;
+
+use crate::config::StatterConfig;
+use crate::entry::run_tournaments;
+use common::{
+	watch::{Snapshot, POLL_INTERVAL},
+	WatchUpdate,
+};
+
+// Runs statter on the source folder, then again every time one of its files changes, until
+// it's dropped. Cups that didn't change are taken from the cache in the destination, so only
+// the affected tournaments are run again.
+pub fn watch_tournaments(source: PathBuf, destination: PathBuf) -> impl Sipper<(), WatchUpdate> {
+	sipper(async move |mut updates| {
+		let mut snapshot = None;
+		let mut run = 0;
+		loop {
+			let new_snapshot = Some(input_snapshot(&source).await);
+			if new_snapshot != snapshot {
+				snapshot = new_snapshot;
+				run += 1;
+				let result = run_tournaments(source.clone(), destination.clone())
+					.with(WatchUpdate::Working)
+					.run(&updates)
+					.await;
+				updates.send(WatchUpdate::Finished { run, result }).await;
+			}
+			time::sleep(POLL_INTERVAL).await;
+		}
+	})
+}
+
+// Modification times of everything statter reads.
+pub async fn input_snapshot(source: &Path) -> Snapshot {
+	let config = StatterConfig::load(source).await.unwrap_or_default();
+	Snapshot::of(config.input_files(source).await).await
+}